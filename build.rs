@@ -23,7 +23,7 @@ fn main() {
         let lib = pkg_config::Config::new()
             .atleast_version("2.8")
             .probe("webkit2gtk-4.1")
-            .expect("Quark can't find webkit2gtk-4.1! Please install it.");
+            .expect("Quark can't find webkit2gtk-4.1! Please install it, `cargo quark doctor` tells you what's missing.");
 
         for path in &lib.include_paths {
             build.include(path);
@@ -0,0 +1,252 @@
+//! Applications with several windows, see [`QuarkApp`].
+//!
+//! Windows opened by a [`QuarkApp`] get a `quark.windows` object, whose methods return
+//! promises. The options of `open` are the keys of `[window]` in `Quark.toml`, on top of the
+//! app's config:
+//!
+//! ```js
+//! await quark.windows.open("preferences", { title: "Preferences", route: "/preferences.html" });
+//! await quark.windows.emitTo("main", "theme", "dark");
+//! console.log(quark.windows.current, await quark.windows.list());
+//! await quark.windows.close("preferences");
+//! ```
+
+use crate::cli;
+use crate::command;
+use crate::config::QuarkConfig;
+use crate::error::QuarkError;
+use crate::handle::QuarkHandle;
+use crate::manifest::WindowManifest;
+use crate::webview::{Webview, WindowEvent};
+use crate::Quark;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::rc::{Rc, Weak};
+
+/// The label of the window `Quark::new` creates, and a good one for an app's first window.
+pub const MAIN_LABEL: &str = "main";
+
+/// The binding `quark.windows` calls through.
+const WINDOWS_BINDING: &str = "__quark_windows";
+
+/// Injected into every page of the app's windows, before any of its own scripts run.
+const SCRIPT: &str = r#"(function() {
+  var quark = window.quark = window.quark || {};
+  var call = function(op, arg) {
+    return window.__quark_windows({ op: op, arg: arg });
+  };
+  quark.windows = {
+    open: function(label, options) { return call("open", [label, options || {}]); },
+    close: function(label) { return call("close", label); },
+    list: function() { return call("list"); },
+    emitTo: function(label, event, payload) {
+      return call("emitTo", [label, event, payload === undefined ? null : payload]);
+    }
+  };
+})();"#;
+
+#[derive(Deserialize)]
+#[serde(tag = "op", content = "arg", rename_all = "camelCase")]
+enum Op {
+    Open(String, WindowManifest),
+    Close(String),
+    List,
+    EmitTo(String, String, Value),
+}
+
+type Setup = Rc<dyn Fn(&mut Quark)>;
+
+/// Runs several windows, each with its own label, config, bindings and route, on one event
+/// loop. By default the application exits once the last of them is closed.
+///
+/// macOS has no window events yet, so the app doesn't notice windows closing there: their
+/// labels stay taken and the app ends with its last window whatever
+/// [`QuarkApp::set_exit_on_last_window_closed`] says.
+///
+/// Clones share the same app, so handlers can keep one to open windows later on. Like
+/// [`Quark`], it stays on the thread that created it.
+///
+/// # Examples
+///
+/// ```rust, ignore
+/// let app = QuarkApp::new(manifest!()?);
+/// app.on_window_created(|quark| {
+///     quark.command("version", |()| -> Result<_, ()> { Ok(env!("CARGO_PKG_VERSION")) });
+/// });
+/// app.open("main", app.config())?;
+/// app.open("preferences", app.config().title("Preferences").route("/preferences.html"))?;
+/// app.run();
+/// ```
+#[derive(Clone)]
+pub struct QuarkApp {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    config: QuarkConfig,
+    args: cli::Args,
+    windows: RefCell<BTreeMap<String, Quark>>,
+    setup: RefCell<Vec<Setup>>,
+    exit_on_last_window_closed: Cell<bool>,
+}
+
+impl QuarkApp {
+    /// Creates an app without any window yet. `config` is what windows opened from JavaScript
+    /// start from, the whole command line is left to the application as with `Quark::new`.
+    pub fn new(config: QuarkConfig) -> Self {
+        Self::with_args(config, cli::Args::from_env())
+    }
+
+    /// Like [`QuarkApp::new`], but applies the dev flags found in `args` to every window,
+    /// usually coming from [`cli::parse_args`].
+    pub fn with_args(config: QuarkConfig, args: cli::Args) -> Self {
+        QuarkApp {
+            inner: Rc::new(Inner {
+                config,
+                args,
+                windows: RefCell::default(),
+                setup: RefCell::default(),
+                exit_on_last_window_closed: Cell::new(true),
+            }),
+        }
+    }
+
+    /// Returns a copy of the app's config, to build the config of a new window from.
+    pub fn config(&self) -> QuarkConfig {
+        self.inner.config.clone()
+    }
+
+    /// Whether the application exits once its last window is closed, which it does by default.
+    /// Otherwise it runs until [`QuarkApp::exit`].
+    pub fn set_exit_on_last_window_closed(&self, exit: bool) {
+        self.inner.exit_on_last_window_closed.set(exit);
+    }
+
+    /// Calls `setup` with every window opened from now on, from Rust or JavaScript, before its
+    /// page loads. It's the place for bindings, commands and listeners, [`Quark::label`] telling
+    /// the windows apart.
+    pub fn on_window_created<F>(&self, setup: F)
+    where
+        F: Fn(&mut Quark) + 'static,
+    {
+        self.inner.setup.borrow_mut().push(Rc::new(setup));
+    }
+
+    /// Opens a window called `label`, failing with `QuarkError::WindowAlreadyOpen` if there's
+    /// one already. Can be called before [`QuarkApp::run`] as well as from handlers while it
+    /// runs.
+    pub fn open(&self, label: &str, config: QuarkConfig) -> Result<QuarkHandle, QuarkError> {
+        if self.inner.windows.borrow().contains_key(label) {
+            return Err(QuarkError::WindowAlreadyOpen(label.to_owned()));
+        }
+
+        let mut quark = Quark::build(config, self.inner.args.clone(), label)?;
+        quark.webview.set_exit_on_close(false);
+        install(&mut quark, Rc::downgrade(&self.inner));
+
+        // A snapshot, so they can open windows themselves and those get set up too
+        let setup = self.inner.setup.borrow().clone();
+        for setup in &setup {
+            setup(&mut quark);
+        }
+
+        let handle = quark.handle();
+        self.inner
+            .windows
+            .borrow_mut()
+            .insert(label.to_owned(), quark);
+        Ok(handle)
+    }
+
+    /// Returns a [`QuarkHandle`] for the window called `label`, if it's open.
+    pub fn window(&self, label: &str) -> Option<QuarkHandle> {
+        self.inner.windows.borrow().get(label).map(Quark::handle)
+    }
+
+    /// Returns the labels of the open windows.
+    pub fn labels(&self) -> Vec<String> {
+        self.inner.windows.borrow().keys().cloned().collect()
+    }
+
+    /// Ends the main loop, making [`QuarkApp::run`] return.
+    pub fn exit(&self) {
+        // The windows may all be gone already
+        Webview::terminate_loop();
+    }
+
+    /// Runs every window until the application exits, returning right away if none is open.
+    pub fn run(self) {
+        // Any of them runs the main loop they share
+        let main_loop = self
+            .inner
+            .windows
+            .borrow()
+            .values()
+            .next()
+            .map(|quark| quark.webview.clone());
+        if let Some(mut webview) = main_loop {
+            webview.run();
+        }
+        for quark in self.inner.windows.borrow().values() {
+            quark.shared.stop();
+        }
+    }
+
+    fn apply(&self, op: Op) -> Result<Value, String> {
+        let window = |label: &str| {
+            self.window(label)
+                .ok_or_else(|| format!("There's no '{label}' window"))
+        };
+        match op {
+            Op::Open(label, options) => {
+                self.open(&label, options.apply(self.config()))
+                    .map_err(|e| format!("Couldn't open '{label}': {e}"))?;
+            }
+            Op::Close(label) => window(&label)?.close(),
+            Op::List => return Ok(json!(self.labels())),
+            Op::EmitTo(label, event, payload) => window(&label)?.emit(&event, payload),
+        }
+        Ok(Value::Null)
+    }
+}
+
+/// Injects `quark.windows` into the window and forgets it once it's closed, its webview being
+/// destroyed along with what it was handed right after (see `Quark::build`).
+fn install(quark: &mut Quark, app: Weak<Inner>) {
+    // A string always serializes
+    let label = serde_json::to_string(&quark.label).unwrap_or_default();
+    quark.webview.init(SCRIPT);
+    quark
+        .webview
+        .init(&format!("window.quark.windows.current = {label};"));
+
+    let returner = quark.webview.clone();
+    let bound = app.clone();
+    quark.webview.bind(WINDOWS_BINDING, move |seq, req| {
+        let (status, result) = match (command::decode_args::<(Op,)>(req), bound.upgrade()) {
+            (Ok((op,)), Some(inner)) => command::settle(QuarkApp { inner }.apply(op)),
+            (Ok(_), None) => command::settle::<(), _>(Err("The application has ended")),
+            (Err(e), _) => command::invalid_args("quark.windows", &e),
+        };
+        returner.r#return(seq, status, &result);
+    });
+
+    let label = quark.label.clone();
+    quark
+        .window_listeners
+        .on_window_event(Box::new(move |event| {
+            if *event != WindowEvent::Closed {
+                return;
+            }
+            let Some(inner) = app.upgrade() else {
+                return;
+            };
+            // Its handle was stopped already, see `Quark::build`
+            inner.windows.borrow_mut().remove(&label);
+            if inner.windows.borrow().is_empty() && inner.exit_on_last_window_closed.get() {
+                QuarkApp { inner }.exit();
+            }
+        }));
+}
@@ -0,0 +1,329 @@
+use crate::output;
+use cargo_metadata::MetadataCommand;
+use image::GenericImage;
+use libquark::cli::bundle::{self, Settings};
+use libquark::manifest::{self, FILE_NAME};
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::io::IsTerminal;
+
+/// The oldest webkit2gtk `build.rs` accepts.
+#[cfg(target_os = "linux")]
+const WEBKIT_MIN_VERSION: &str = "2.8";
+
+/// Icon sizes desktops look for, anything else gets scaled.
+const ICON_SIZES: &[u32] = &[16, 24, 32, 48, 64, 128, 256, 512, 1024];
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    /// Worth knowing about, but nothing stops you from building.
+    Warn,
+    Fail,
+}
+
+#[derive(Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    message: String,
+}
+
+#[derive(Default, Serialize)]
+struct Report {
+    ok: bool,
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn add(&mut self, name: &'static str, status: Status, message: impl Into<String>) {
+        self.checks.push(Check {
+            name,
+            status,
+            message: message.into(),
+        });
+    }
+
+    fn failures(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.status == Status::Fail)
+            .count()
+    }
+}
+
+pub(crate) fn run(args: &[String]) -> bundle::Result<()> {
+    let json = match args {
+        [] => false,
+        [flag] if flag == "--json" => true,
+        [other, ..] => {
+            return Err(format!(
+                "'{other}' is an unknown option silly. Use 'cargo quark help' to list them."
+            )
+            .into())
+        }
+    };
+
+    let mut report = Report::default();
+    tools(&mut report);
+    #[cfg(target_os = "linux")]
+    {
+        libraries(&mut report);
+        display(&mut report);
+    }
+    project(&mut report);
+
+    let failures = report.failures();
+    report.ok = failures == 0;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        // No escape codes in files and pipes
+        let colour = std::io::stdout().is_terminal();
+        for check in &report.checks {
+            let status = match (check.status, colour) {
+                (Status::Pass, true) => "\x1b[1;32mpass\x1b[0m",
+                (Status::Warn, true) => "\x1b[1;33mwarn\x1b[0m",
+                (Status::Fail, true) => "\x1b[1;31mfail\x1b[0m",
+                (Status::Pass, false) => "pass",
+                (Status::Warn, false) => "warn",
+                (Status::Fail, false) => "fail",
+            };
+            println!("  {status}  {:<17}{}", check.name, check.message);
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{failures} problem(s) found.").into());
+    }
+    if !json {
+        println!("\nEverything looks good!");
+    }
+    Ok(())
+}
+
+fn tools(report: &mut Report) {
+    for (name, what) in [
+        ("rustc", "the Rust compiler"),
+        ("cargo", "Rust's package manager"),
+    ] {
+        match output(name, &["--version"]) {
+            Some(version) => report.add(name, Status::Pass, version),
+            None => report.add(name, Status::Fail, format!("{what} isn't installed")),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn libraries(report: &mut Report) {
+    if output("pkg-config", &["--version"]).is_none() {
+        report.add(
+            "pkg-config",
+            Status::Fail,
+            "isn't installed, it's how webkit2gtk and GTK are found",
+        );
+        return;
+    }
+
+    let webkit = output("pkg-config", &["--modversion", "webkit2gtk-4.1"]);
+    let recent = std::process::Command::new("pkg-config")
+        .args(["--atleast-version", WEBKIT_MIN_VERSION, "webkit2gtk-4.1"])
+        .status()
+        .is_ok_and(|status| status.success());
+    match webkit {
+        Some(version) if recent => report.add("webkit2gtk-4.1", Status::Pass, version),
+        Some(version) => report.add(
+            "webkit2gtk-4.1",
+            Status::Fail,
+            format!("{version} is too old, Quark needs {WEBKIT_MIN_VERSION} or newer"),
+        ),
+        None => report.add(
+            "webkit2gtk-4.1",
+            Status::Fail,
+            "not found, install its development package (e.g. libwebkit2gtk-4.1-dev)",
+        ),
+    }
+
+    match output("pkg-config", &["--modversion", "gtk+-3.0"]) {
+        Some(version) => report.add("gtk+-3.0", Status::Pass, version),
+        None => report.add(
+            "gtk+-3.0",
+            Status::Fail,
+            "not found, install its development package (e.g. libgtk-3-dev)",
+        ),
+    }
+}
+
+/// Windows need somewhere to show up, which CI machines usually don't have.
+#[cfg(target_os = "linux")]
+fn display(report: &mut Report) {
+    let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+    if let Some(wayland) = var("WAYLAND_DISPLAY") {
+        let socket = match var("XDG_RUNTIME_DIR") {
+            Some(runtime) => std::path::Path::new(&runtime).join(&wayland),
+            None => std::path::Path::new(&wayland).to_path_buf(),
+        };
+        if socket.exists() {
+            report.add("display", Status::Pass, format!("Wayland ({wayland})"));
+            return;
+        }
+    }
+
+    if let Some(display) = var("DISPLAY") {
+        // `:99` and `:0.0` are local and have a socket, `host:0` is remote
+        let local = display
+            .strip_prefix(':')
+            .and_then(|number| number.split('.').next())
+            .map(|number| std::path::Path::new("/tmp/.X11-unix").join(format!("X{number}")));
+        match local {
+            Some(socket) if !socket.exists() => {}
+            Some(_) => {
+                report.add("display", Status::Pass, format!("X11 ({display})"));
+                return;
+            }
+            None => {
+                report.add("display", Status::Pass, format!("remote X11 ({display})"));
+                return;
+            }
+        }
+    }
+
+    let hint = if in_path("xvfb-run") {
+        "run your app with `xvfb-run` on headless machines"
+    } else {
+        "install Xvfb to run your app on headless machines"
+    };
+    report.add(
+        "display",
+        Status::Warn,
+        format!("none found, windows can't open; {hint}"),
+    );
+}
+
+#[cfg(target_os = "linux")]
+fn in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+fn project(report: &mut Report) {
+    let metadata = match MetadataCommand::new().no_deps().exec() {
+        Ok(metadata) => metadata,
+        Err(_) => {
+            report.add(
+                "project",
+                Status::Warn,
+                "not in a cargo project, skipping the project checks",
+            );
+            return;
+        }
+    };
+
+    // `cargo build` creates it, but it has to be allowed to
+    let target_dir = metadata.target_directory.as_std_path();
+    let probe = target_dir.join(format!(".quark-doctor-{}", std::process::id()));
+    let writable = std::fs::create_dir_all(target_dir)
+        .and_then(|()| std::fs::write(&probe, b""))
+        .and_then(|()| std::fs::remove_file(&probe));
+    match writable {
+        Ok(()) => report.add("target dir", Status::Pass, target_dir.display().to_string()),
+        Err(e) => report.add(
+            "target dir",
+            Status::Fail,
+            format!("{} isn't writable ({e})", target_dir.display()),
+        ),
+    }
+
+    let has_bundle = metadata.workspace_packages().iter().any(|package| {
+        package.metadata.get("bundle").is_some()
+            || package.manifest_path.with_file_name(FILE_NAME).exists()
+    });
+    if !has_bundle {
+        report.add(
+            "bundle metadata",
+            Status::Warn,
+            "no Quark.toml or [package.metadata.bundle] section, bundles get default values",
+        );
+    }
+
+    let settings = match std::env::current_dir()
+        .map_err(bundle::Error::from)
+        .and_then(|dir| Settings::new(dir, None))
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            report.add("bundle metadata", Status::Fail, e.to_string());
+            return;
+        }
+    };
+    if has_bundle {
+        let identifier = settings.bundle_identifier();
+        if manifest::is_valid_identifier(&identifier) {
+            report.add(
+                "bundle metadata",
+                Status::Pass,
+                format!(
+                    "{} {} ({identifier}), from the '{}' binary",
+                    settings.bundle_name(),
+                    settings.version_string(),
+                    settings.binary_name()
+                ),
+            );
+        } else {
+            report.add(
+                "bundle metadata",
+                Status::Fail,
+                format!("'{identifier}' isn't a valid identifier, it looks like 'com.example.app'"),
+            );
+        }
+    }
+
+    icons(report, &settings);
+}
+
+fn icons(report: &mut Report, settings: &Settings) {
+    let mut sizes = Vec::new();
+    for path in settings.icon_files() {
+        let path = match path {
+            Ok(path) => path,
+            Err(e) => return report.add("icons", Status::Fail, e.to_string()),
+        };
+        if path.extension() == Some(OsStr::new("icns")) {
+            sizes.push("icns".to_owned());
+            continue;
+        }
+        let (width, height) = match image::open(&path) {
+            Ok(icon) => icon.dimensions(),
+            Err(e) => return report.add("icons", Status::Fail, format!("{}: {e}", path.display())),
+        };
+        if width != height {
+            return report.add(
+                "icons",
+                Status::Fail,
+                format!(
+                    "{} is {width}x{height}, icons have to be square",
+                    path.display()
+                ),
+            );
+        }
+        if !ICON_SIZES.contains(&width) {
+            report.add(
+                "icons",
+                Status::Warn,
+                format!(
+                    "{} is {width}x{width}, desktops expect one of {ICON_SIZES:?}",
+                    path.display()
+                ),
+            );
+        }
+        sizes.push(format!("{width}x{height}"));
+    }
+
+    if sizes.is_empty() {
+        report.add("icons", Status::Warn, "none set, bundles get no icon");
+    } else {
+        report.add("icons", Status::Pass, sizes.join(", "));
+    }
+}
@@ -0,0 +1,215 @@
+// `cargo quark`, works on the project in the current directory
+use libquark::cli::bundle::{self, bundle_project, print_error, print_finished, Settings};
+use libquark::cli::env;
+use std::ffi::OsString;
+use std::process::{Command, ExitCode};
+
+mod doctor;
+mod new;
+
+const HELP: &str = "\
+Usage: cargo quark <COMMAND> [OPTION]...
+
+Commands:
+  new <PATH>      Create a new Quark project in the PATH folder, named after it.
+                  --template <NAME>  Template to start from (default vanilla).
+                  --identifier <ID>  Bundle identifier (default com.example.<name>).
+                  --list             List the templates.
+  dev             Run the project with the live server and hot reload.
+                  --host <HOST>   Address of the live server (default 127.0.0.1).
+                  --port <PORT>   Port of the live server (default 24114).
+                  --dev-url <URL> Load the frontend from an external dev server (e.g. Vite).
+                  --bin <NAME>    Binary to run, if the project has several.
+                  -- <ARGS>       Arguments for the application itself.
+  build           Build the project in the release profile, any option goes to `cargo build`.
+  bundle          Build the project and package it for your platform.
+                  --bin <NAME>    Binary to bundle, if the project has several.
+  info            Show what Quark knows about your system and project.
+  doctor          Check that your system and project can build Quark applications.
+                  --json          Print the report as JSON.
+  help            Display this help message.";
+
+fn main() -> ExitCode {
+    // Cargo runs us as `cargo-quark quark <COMMAND>`
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().is_some_and(|arg| arg == "quark") {
+        args.next();
+    }
+
+    let Some(command) = args.next() else {
+        println!("{HELP}");
+        return ExitCode::FAILURE;
+    };
+    let args: Vec<String> = args.collect();
+    let result = match command.as_str() {
+        "new" => new::run(&args),
+        "dev" => dev(&args),
+        "build" => build(&args),
+        "bundle" => bundle(&args),
+        "info" => info(),
+        "doctor" => doctor::run(&args),
+        "help" | "--help" | "-h" => {
+            println!("{HELP}");
+            Ok(())
+        }
+        other => Err(format!(
+            "'{other}' is an unknown command silly. Use 'cargo quark help' to list the commands."
+        )
+        .into()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            let _ = print_error(&e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Returns the cargo that ran us, so toolchain overrides carry over.
+fn cargo() -> Command {
+    Command::new(std::env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo")))
+}
+
+/// Runs `command`, failing if it doesn't exit successfully.
+fn status(mut command: Command) -> bundle::Result<()> {
+    let status = command.status()?;
+    if !status.success() {
+        return Err(format!("{command:?} failed ({status})").into());
+    }
+    Ok(())
+}
+
+/// Returns the value of `flag`, given either as `--flag=value` or as the next argument.
+fn flag_value<'a>(
+    flag: &str,
+    inline: Option<&'a str>,
+    args: &mut impl Iterator<Item = &'a String>,
+) -> bundle::Result<&'a str> {
+    inline
+        .or_else(|| args.next().map(String::as_str))
+        .ok_or_else(|| format!("'{flag}' needs a value silly.").into())
+}
+
+fn dev(args: &[String]) -> bundle::Result<()> {
+    let mut cargo = cargo();
+    cargo.arg("run").env(env::LIVE, "1");
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+        match flag {
+            "--host" => {
+                cargo.env(env::HOST, flag_value(flag, inline, &mut args)?);
+            }
+            "--port" => {
+                let port = flag_value(flag, inline, &mut args)?;
+                if port.parse::<u16>().is_err() {
+                    return Err(
+                        format!("'{port}' isn't a port silly, it goes from 0 to 65535.").into(),
+                    );
+                }
+                cargo.env(env::PORT, port);
+            }
+            "--dev-url" => {
+                cargo.env(env::DEV_URL, flag_value(flag, inline, &mut args)?);
+            }
+            "--bin" => {
+                cargo.args(["--bin", flag_value(flag, inline, &mut args)?]);
+            }
+            "--" => {
+                cargo.arg("--").args(args.by_ref());
+            }
+            other => {
+                return Err(format!(
+                    "'{other}' is an unknown option silly. Use 'cargo quark help' to list them."
+                )
+                .into())
+            }
+        }
+    }
+    status(cargo)
+}
+
+fn build(args: &[String]) -> bundle::Result<()> {
+    let mut cargo = cargo();
+    cargo.args(["build", "--release"]).args(args);
+    status(cargo)
+}
+
+fn bundle(args: &[String]) -> bundle::Result<()> {
+    let mut bin = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+        match flag {
+            "--bin" => bin = Some(flag_value(flag, inline, &mut args)?.to_owned()),
+            other => {
+                return Err(format!(
+                    "'{other}' is an unknown option silly. Use 'cargo quark help' to list them."
+                )
+                .into())
+            }
+        }
+    }
+
+    let settings = Settings::new(std::env::current_dir()?, bin)?;
+    let mut cargo = cargo();
+    cargo.args(["build", "--release", "--bin", settings.binary_name()]);
+    status(cargo)?;
+
+    let paths = bundle_project(settings)?;
+    print_finished(&paths)
+}
+
+/// Returns the first line `program` prints with `args`, if it could be run.
+pub(crate) fn output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    stdout.lines().next().map(|line| line.trim().to_owned())
+}
+
+fn info() -> bundle::Result<()> {
+    let unknown = || "not found".to_owned();
+    println!("System");
+    println!("  cargo-quark  {}", env!("CARGO_PKG_VERSION"));
+    println!(
+        "  rustc        {}",
+        output("rustc", &["--version"]).unwrap_or_else(unknown)
+    );
+    println!(
+        "  cargo        {}",
+        output("cargo", &["--version"]).unwrap_or_else(unknown)
+    );
+    println!(
+        "  platform     {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    if cfg!(target_os = "linux") {
+        let webkit = output("pkg-config", &["--modversion", "webkit2gtk-4.1"]);
+        println!("  webkit2gtk   {}", webkit.unwrap_or_else(unknown));
+    }
+
+    println!("\nProject");
+    match Settings::new(std::env::current_dir()?, None) {
+        Ok(settings) => {
+            println!("  name         {}", settings.bundle_name());
+            println!("  version      {}", settings.version_string());
+            println!("  identifier   {}", settings.bundle_identifier());
+            println!("  binary       {}", settings.binary_name());
+        }
+        Err(e) => println!("  none ({e})"),
+    }
+    Ok(())
+}
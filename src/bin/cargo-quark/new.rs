@@ -0,0 +1,177 @@
+use crate::flag_value;
+use include_dir::{include_dir, Dir, DirEntry, File};
+use libquark::cli::bundle;
+use libquark::manifest;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Every template, plus `common` which holds the files they share. Nothing is downloaded,
+/// they're all part of the binary.
+static TEMPLATES: Dir<'static> = include_dir!("$CARGO_MANIFEST_DIR/templates");
+static ICONS: Dir<'static> = include_dir!("$CARGO_MANIFEST_DIR/assets/bundle_images");
+
+const COMMON: &str = "common";
+const DEFAULT_TEMPLATE: &str = "vanilla";
+
+/// What new projects depend on. The templates use `frontend!` and `manifest!`, which aren't in
+/// a release yet, so it's the repository until then and `env!("CARGO_PKG_VERSION")` after.
+const QUARK_DEPENDENCY: &str = concat!("{ git = \"", env!("CARGO_PKG_REPOSITORY"), "\" }");
+
+/// What to run after creating a project from a template, `cargo quark dev` being implied.
+fn next_steps(template: &str) -> &'static str {
+    match template {
+        "typescript" => "npm install\n  npm run build\n  ",
+        _ => "",
+    }
+}
+
+pub(crate) fn run(args: &[String]) -> bundle::Result<()> {
+    let mut path = None;
+    let mut template = DEFAULT_TEMPLATE;
+    let mut identifier = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+        match flag {
+            "--template" | "-t" => template = flag_value(flag, inline, &mut args)?,
+            "--identifier" => identifier = Some(flag_value(flag, inline, &mut args)?.to_owned()),
+            "--list" => {
+                for name in templates() {
+                    println!("{name}");
+                }
+                return Ok(());
+            }
+            _ if path.is_none() && !flag.starts_with('-') => path = Some(PathBuf::from(arg)),
+            other => {
+                return Err(format!(
+                    "'{other}' is an unknown option silly. Use 'cargo quark help' to list them."
+                )
+                .into())
+            }
+        }
+    }
+
+    let Some(root) = path else {
+        return Err("Usage: cargo quark new <PATH> [--template NAME] [--identifier ID]".into());
+    };
+    let name = root
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Can't tell the project name from {root:?}."))?
+        .to_owned();
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && name.starts_with(|c: char| c.is_ascii_alphabetic());
+    if !valid {
+        return Err(format!(
+            "'{name}' isn't a valid package name silly, use letters, numbers, '-' and '_'."
+        )
+        .into());
+    }
+
+    let identifier = identifier
+        .unwrap_or_else(|| format!("com.example.{}", name.replace('_', "-").to_lowercase()));
+    if !manifest::is_valid_identifier(&identifier) {
+        return Err(format!(
+            "'{identifier}' isn't a valid identifier silly, it looks like 'com.example.app'."
+        )
+        .into());
+    }
+
+    if template == COMMON || TEMPLATES.get_dir(template).is_none() {
+        return Err(format!(
+            "There's no '{template}' template, pick one of: {}.",
+            templates().collect::<Vec<_>>().join(", ")
+        )
+        .into());
+    }
+
+    if root.exists() {
+        return Err(format!("{root:?} already exists.").into());
+    }
+
+    let variables = [
+        ("{{name}}", name.as_str()),
+        ("{{identifier}}", identifier.as_str()),
+        ("{{quark_dependency}}", QUARK_DEPENDENCY),
+    ];
+
+    // The template's own files win over the shared ones
+    let mut files = BTreeMap::new();
+    for dir in [COMMON, template] {
+        let dir = TEMPLATES.get_dir(dir).expect("The template exists");
+        for file in files_in(dir) {
+            let path = file.path().strip_prefix(dir.path()).unwrap_or(file.path());
+            files.insert(output_path(path), file.contents());
+        }
+    }
+    for icon in ICONS.files() {
+        files.insert(Path::new("icons").join(icon.path()), icon.contents());
+    }
+
+    for (path, contents) in files {
+        let path = root.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match std::str::from_utf8(contents) {
+            Ok(text) => {
+                let text = variables
+                    .iter()
+                    .fold(text.to_owned(), |text, (key, value)| {
+                        text.replace(key, value)
+                    });
+                fs::write(path, text)?;
+            }
+            // Images and such are copied as they are
+            Err(_) => fs::write(path, contents)?,
+        }
+    }
+
+    println!(
+        "Created '{name}' from the {template} template, run it with:\n  cd {}\n  {}cargo quark dev",
+        root.display(),
+        next_steps(template)
+    );
+    Ok(())
+}
+
+/// Returns the names of the templates.
+fn templates() -> impl Iterator<Item = &'static str> {
+    TEMPLATES
+        .dirs()
+        .filter_map(|dir| dir.path().to_str())
+        .filter(|name| *name != COMMON)
+}
+
+/// Returns every file in `dir`, recursively.
+fn files_in(dir: &'static Dir<'static>) -> Vec<&'static File<'static>> {
+    dir.entries()
+        .iter()
+        .flat_map(|entry| match entry {
+            DirEntry::Dir(dir) => files_in(dir),
+            DirEntry::File(file) => vec![file],
+        })
+        .collect()
+}
+
+/// Turns a path in a template into the one written out. Files that would confuse cargo or git
+/// while they're in the templates folder are stored under another name: `Cargo.toml.tmpl` for
+/// `Cargo.toml` and `_gitignore` for `.gitignore`.
+fn output_path(path: &Path) -> PathBuf {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return path.to_path_buf();
+    };
+    let name = name.strip_suffix(".tmpl").unwrap_or(name);
+    let name = match name.strip_prefix('_') {
+        Some(hidden) => format!(".{hidden}"),
+        None => name.to_owned(),
+    };
+    path.with_file_name(name)
+}
@@ -2,11 +2,11 @@
 use libquark::prelude::*;
 
 fn main() -> Result<(), QuarkError> {
-    let config = QuarkConfig::new()
-        .title("A Quark Application")
-        .resizable(SizeHint::FIXED);
+    // The window settings are in Quark.toml
+    let config = manifest!()?;
 
-    let quark = Quark::new(config)?;
+    // Opts into the dev flags, so `cargo run -- --live` works
+    let quark = Quark::with_args(config, libquark::cli::parse_args())?;
     quark.run();
     Ok(())
 }
@@ -0,0 +1,20 @@
+use crate::error::QuarkError;
+use crate::Quark;
+
+/// Points the webview at an external frontend dev server (Vite, webpack...), which takes care
+/// of serving and hot reloading the frontend by itself. Bindings and `window.quark` are
+/// injected into every page, so they work the same as with the embedded frontend.
+pub fn build_dev_url(quark: &mut Quark, url: &str) -> Result<(), QuarkError> {
+    let url = if quark.config.route.is_some() {
+        format!(
+            "{}{}",
+            url.trim_end_matches('/'),
+            quark.config.route_or_index()
+        )
+    } else {
+        url.to_owned()
+    };
+    quark.webview.navigate(&url)?;
+    log::info!("Using the frontend dev server at {url}");
+    Ok(())
+}
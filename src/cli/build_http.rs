@@ -1,31 +1,263 @@
-use crate::cli::QUARKFOLDER;
+use crate::cli::build_static::asset_path;
 use crate::error::QuarkError;
+use crate::handle::QuarkHandle;
+use crate::mime;
 use crate::Quark;
-use std::sync::Arc;
-use tiny_http::{Response, Server};
+use include_dir::Dir;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Cursor, ErrorKind};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Lets the watcher swap stylesheets in place instead of reloading the whole page.
+const LIVE_RELOAD_JS: &str = r#"(function() {
+  window.__quark_live = {
+    css: function() {
+      document.querySelectorAll('link[rel="stylesheet"]').forEach(function(link) {
+        var url = new URL(link.href);
+        url.searchParams.set("quark-reload", Date.now());
+        link.href = url.toString();
+      });
+    },
+  };
+})();"#;
+
+/// How long the watcher waits for more changes before reloading, editors tend to write a file
+/// in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Where the live server reads the frontend from.
+enum Files {
+    /// The frontend folder on disk, edits show up right away.
+    Disk(PathBuf),
+    /// The compile-time snapshot, used when the folder can't be found on disk.
+    Embedded(Dir<'static>),
+}
+
+impl Files {
+    /// Returns the contents of the file `url` points to, along with its path in the frontend.
+    fn read(&self, url: &str) -> Option<(Vec<u8>, String)> {
+        let path = asset_path(url.split(['?', '#']).next().unwrap_or_default());
+        let data = match self {
+            Files::Disk(root) => {
+                // Don't let requests climb out of the frontend folder
+                let relative = Path::new(&path);
+                if !relative
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_)))
+                {
+                    return None;
+                }
+                std::fs::read(root.join(relative)).ok()
+            }
+            Files::Embedded(dir) => dir.get_file(&path).map(|file| file.contents().to_vec()),
+        };
+        data.map(|data| (data, path))
+    }
+}
 
 pub fn build_http(quark: &mut Quark) -> Result<(), QuarkError> {
-    let server =
-        Server::http("127.0.0.1:24114").map_err(|_| QuarkError::ServerPortIsntAvailable)?;
-    let addr = server.server_addr();
+    let (host, port) = (quark.config.dev_host.as_str(), quark.config.dev_port);
+    let server = match Server::http((host, port)) {
+        Ok(server) => server,
+        // Only a port in use has a way out, a bad host or a privileged port fails either way
+        Err(e)
+            if e.downcast_ref::<io::Error>().map(io::Error::kind) == Some(ErrorKind::AddrInUse) =>
+        {
+            log::warn!("Port {port} is in use, letting the operating system pick one.");
+            Server::http((host, 0)).map_err(|e| {
+                log::error!("Couldn't start the live server on {host}, {e}");
+                QuarkError::ServerPortIsntAvailable
+            })?
+        }
+        Err(e) => {
+            log::error!("Couldn't start the live server on {host}:{port}, {e}");
+            return Err(QuarkError::ServerError);
+        }
+    };
+    let addr = server
+        .server_addr()
+        .to_ip()
+        .ok_or(QuarkError::ServerError)?;
+
+    let frontend = quark
+        .config
+        .frontend
+        .as_ref()
+        .ok_or(QuarkError::FrontendPathMissing)?;
 
-    let shared_frontend_path = Arc::new(QUARKFOLDER.clone());
+    let files = match frontend.source().filter(|source| source.is_dir()) {
+        Some(source) => Files::Disk(source.to_path_buf()),
+        None => {
+            log::warn!("Couldn't find the frontend folder on disk, hot reload is off.");
+            Files::Embedded(frontend.dir.clone())
+        }
+    };
+
+    if let Files::Disk(root) = &files {
+        watch(root, quark.handle())?;
+        quark.webview.init(LIVE_RELOAD_JS);
+    }
+
+    let files = Arc::new(files);
     std::thread::spawn({
-        let shared_frontend_path = Arc::clone(&shared_frontend_path);
+        let files = Arc::clone(&files);
         move || {
             for request in server.incoming_requests() {
-                let requested_path = request.url().trim_start_matches('/');
-                if let Some(file) = shared_frontend_path.get_file(requested_path) {
-                    let response = Response::from_data(file.contents_utf8().unwrap_or_default());
-                    if let Err(err) = request.respond(response) {
-                        eprintln!("Failed to respond: {}", err);
-                    }
+                let response = respond(&files, &request);
+                if let Err(err) = request.respond(response) {
+                    log::warn!("Failed to respond: {}", err);
                 }
             }
         }
     });
 
-    let uri = format!("https://{}/index.html", addr); // mv to https
-    quark.webview.navigate(&uri);
+    // `SocketAddr` puts IPv6 addresses between brackets already
+    let uri = format!("http://{addr}{}", quark.config.route_or_index());
+    log::info!("Live server running at {uri}");
+    quark.webview.navigate(&uri)
+}
+
+fn respond(files: &Files, request: &Request) -> Response<Cursor<Vec<u8>>> {
+    // tiny_http leaves the body out of the answer to a HEAD on its own
+    if !matches!(request.method(), Method::Get | Method::Head) {
+        return Response::from_string("405 Method Not Allowed")
+            .with_status_code(405)
+            .with_header(header("Allow", "GET, HEAD"));
+    }
+
+    let Some((data, path)) = files.read(request.url()) else {
+        return Response::from_string("404 Not Found")
+            .with_status_code(404)
+            .with_header(header("Content-Type", mime::from_extension("txt")));
+    };
+
+    // Everything is revalidated so edits are never hidden behind a stale cache
+    let etag = {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        format!("\"{:016x}\"", hasher.finish())
+    };
+    let headers = [
+        header("Content-Type", mime::from_path(&path)),
+        header("Cache-Control", "no-cache"),
+        header("ETag", &etag),
+        header("Accept-Ranges", "bytes"),
+    ];
+
+    if request_header(request, "If-None-Match").is_some_and(|tags| {
+        tags.split(',')
+            .any(|tag| tag.trim() == etag || tag.trim() == "*")
+    }) {
+        return with_headers(Response::from_data(Vec::new()), headers).with_status_code(304);
+    }
+
+    let length = data.len() as u64;
+    match request_header(request, "Range").and_then(|range| parse_range(range, length)) {
+        None => with_headers(Response::from_data(data), headers),
+        Some(Some((start, end))) => {
+            let content_range = format!("bytes {start}-{end}/{length}");
+            let slice = data[start as usize..=end as usize].to_vec();
+            with_headers(Response::from_data(slice), headers)
+                .with_status_code(206)
+                .with_header(header("Content-Range", &content_range))
+        }
+        Some(None) => with_headers(Response::from_data(Vec::new()), headers)
+            .with_status_code(416)
+            .with_header(header("Content-Range", &format!("bytes */{length}"))),
+    }
+}
+
+/// Parses a `Range` header for a file of `length` bytes into the first and last byte to send,
+/// both inclusive. `None` means the header is ignored and the whole file is sent, as happens
+/// with several ranges or anything else that isn't a single byte range. `Some(None)` means the
+/// range can't be satisfied.
+pub fn parse_range(range: &str, length: u64) -> Option<Option<(u64, u64)>> {
+    let (start, end) = range.trim().strip_prefix("bytes=")?.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+    Some(match (start.is_empty(), end.is_empty()) {
+        // `bytes=-500`, the last 500 bytes
+        (true, false) => {
+            let suffix = end.parse::<u64>().ok()?.min(length);
+            (suffix > 0).then(|| (length - suffix, length - 1))
+        }
+        // `bytes=500-`, everything from byte 500
+        (false, true) => {
+            let start = start.parse::<u64>().ok()?;
+            (start < length).then(|| (start, length - 1))
+        }
+        (false, false) => {
+            let (start, end) = (start.parse::<u64>().ok()?, end.parse::<u64>().ok()?);
+            if start > end {
+                return None;
+            }
+            (start < length).then(|| (start, end.min(length - 1)))
+        }
+        (true, true) => return None,
+    })
+}
+
+fn request_header<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.as_str().as_str().eq_ignore_ascii_case(name))
+        .map(|header| header.value.as_str())
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("Invalid header")
+}
+
+fn with_headers<const N: usize>(
+    mut response: Response<Cursor<Vec<u8>>>,
+    headers: [Header; N],
+) -> Response<Cursor<Vec<u8>>> {
+    for header in headers {
+        response.add_header(header);
+    }
+    response
+}
+
+/// Watches `root` and reloads the page whenever something in it changes, stylesheets are
+/// swapped in place when they're the only thing that changed.
+fn watch(root: &Path, handle: QuarkHandle) -> Result<(), QuarkError> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(sender).map_err(|_| QuarkError::FileWatcherError)?;
+    watcher
+        .watch(root, RecursiveMode::Recursive)
+        .map_err(|_| QuarkError::FileWatcherError)?;
+
+    std::thread::spawn(move || {
+        let _watcher = watcher; // stops watching once dropped
+        let changed = |event: notify::Result<notify::Event>| match event {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => event.paths,
+            _ => Vec::new(),
+        };
+
+        while let Ok(event) = receiver.recv() {
+            let mut paths = changed(event);
+            while let Ok(event) = receiver.recv_timeout(DEBOUNCE) {
+                paths.extend(changed(event));
+            }
+            if paths.is_empty() {
+                continue;
+            }
+
+            let css_only = paths
+                .iter()
+                .all(|path| path.extension().is_some_and(|ext| ext == "css"));
+            if css_only {
+                handle.eval("window.__quark_live && window.__quark_live.css();");
+            } else {
+                handle.eval("location.reload();");
+            }
+        }
+    });
     Ok(())
 }
@@ -1,15 +1,73 @@
-use crate::cli::QUARKFOLDER;
 use crate::error::QuarkError;
+use crate::mime;
+use crate::webview::Webview;
 use crate::Quark;
-use std::path::Path;
+use include_dir::Dir;
+
+/// The scheme embedded frontends are served from, `quark://app/index.html`.
+pub const SCHEME: &str = "quark";
 
 pub fn build_static(quark: &mut Quark) -> Result<(), QuarkError> {
-    let path = QUARKFOLDER
-        .get_file(Path::new("index.html"))
-        .ok_or(QuarkError::FrontendPathMissing)?
-        .contents_utf8()
-        .ok_or(QuarkError::IncludeDirCouldntConvertToUTF8)?;
+    let frontend = quark
+        .config
+        .frontend
+        .as_ref()
+        .ok_or(QuarkError::FrontendPathMissing)?;
+
+    if !frontend.dir.contains("index.html") {
+        return Err(QuarkError::FrontendPathMissing);
+    }
+
+    let route = quark.config.route_or_index();
+    serve(&mut quark.webview, frontend.dir.clone(), &route)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn serve(webview: &mut Webview, dir: Dir<'static>, route: &str) -> Result<(), QuarkError> {
+    webview.register_scheme(SCHEME, move |path| {
+        let file = dir.get_file(asset_path(path))?;
+        Some((file.contents(), mime::from_path(file.path())))
+    });
+    webview.navigate(&format!("{SCHEME}://app{route}"))
+}
 
-    quark.webview.set_html(path);
+#[cfg(target_os = "macos")]
+fn serve(webview: &mut Webview, dir: Dir<'static>, _route: &str) -> Result<(), QuarkError> {
+    // `quark://` isn't there on macOS yet, fall back to loading the page on its own.
+    let html = dir
+        .get_file("index.html")
+        .and_then(|file| file.contents_utf8())
+        .ok_or(QuarkError::IncludeDirCouldntConvertToUTF8)?;
+    webview.set_html(html);
     Ok(())
 }
+
+/// Turns the path of a `quark://` request into the path of the file inside the embedded
+/// [`Dir`], e.g. `/img/a%20b.png` into `img/a b.png` and `/docs/` into `docs/index.html`.
+pub fn asset_path(path: &str) -> String {
+    let path = percent_decode(path.trim_start_matches('/'));
+    if path.is_empty() || path.ends_with('/') {
+        format!("{path}index.html")
+    } else {
+        path
+    }
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
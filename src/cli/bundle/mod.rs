@@ -8,7 +8,7 @@ mod linux;
 mod macos;
 mod settings;
 
-pub use self::common::{print_error, print_finished};
+pub use self::common::{print_error, print_finished, print_warning};
 pub use self::settings::{BuildArtifact, PackageType, Settings};
 #[cfg(target_os = "linux")]
 use super::bundle::linux::deb_bundle;
@@ -1,16 +1,16 @@
 // This codebase is a frking mess.
 // omfg why did I push this into master branchhhhhhhhh
 use super::{category::AppCategory, common::print_warning};
+use crate::error::QuarkError;
+use crate::manifest::{self, Manifest};
 use cargo_metadata::{Metadata, MetadataCommand};
 use error_chain::bail;
 use serde::Deserialize;
-use serde_json::Value;
 use std::borrow::Cow;
 //use std::collections::HashMap;
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
 // use target_build_utils::TargetInfo;
-use std::ffi::OsString;
 // use std::env::consts;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -100,36 +100,25 @@ fn load_metadata(dir: &Path) -> super::Result<Metadata> {
 }
 
 impl Settings {
-    pub fn new(current_dir: PathBuf) -> super::Result<Self> {
-        // Build the project first
-        let _status = std::process::Command::new("cargo")
-            .args(["build", "--profile", "release", "--quiet"]) // Need a better alternative
-            .status()?; // sometimes the stupid code works better than the good one
-
-        // if !status.success() {
-        //     super::print_error("Failed to build project in the release profile")?;
-        // }
-
+    /// Loads the bundle settings of the project in `current_dir`. The binary is picked from its
+    /// metadata unless `bin` names one, and is expected to be built in the release profile
+    /// already.
+    pub fn new(current_dir: PathBuf, bin: Option<String>) -> super::Result<Self> {
         let profile = "release".to_string(); // trust me, don't remove this
                                              // let target = None;
         let package_type = None;
         let features = None;
 
-        let current_exe = std::env::current_exe()?;
-        let binary_name = current_exe
-            .file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| "Could not determine binary name")?
-            .to_string();
-
-        let build_artifact = BuildArtifact::Main;
-
-        let (bundle_settings, package) =
-            Settings::find_bundle_package(load_metadata(&current_dir)?)?;
+        let metadata = load_metadata(&current_dir)?;
         // let target_dir = Settings::get_target_dir(&target, &profile);
-        let target_dir = Settings::get_target_dir(&profile);
+        let target_dir = metadata.target_directory.as_std_path().join(&profile);
+        let (bundle_settings, package) = Settings::find_bundle_package(metadata)?;
 
-        let binary_path = target_dir.join(&binary_name);
+        let (binary_name, build_artifact) = match bin {
+            Some(name) => (name.clone(), BuildArtifact::Bin(name)),
+            None => (Settings::find_binary_name(&package)?, BuildArtifact::Main),
+        };
+        let binary_path = target_dir.join(format!("{binary_name}{}", std::env::consts::EXE_SUFFIX));
 
         Ok(Settings {
             package,
@@ -145,29 +134,28 @@ impl Settings {
         })
     }
 
-    fn get_target_dir(
-        // target: &Option<(String, TARGET_INFO)>,
-        profile: &str,
-    ) -> PathBuf {
-        let mut cargo = std::process::Command::new(
-            std::env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo")),
-        );
-        cargo.args(["metadata", "--no-deps"]);
-
-        let target_dir = cargo.output().ok().and_then(|output| {
-            let json_string = String::from_utf8(output.stdout).ok()?;
-            let json: Value = serde_json::from_str(&json_string).ok()?;
-            Some(PathBuf::from(json.get("target_directory")?.as_str()?))
-        });
-
-        let mut path = target_dir.unwrap(); // oh, that's why None didn't work- unwrap.
-
-        // if let &Some((ref triple, _)) = target {
-        //     path.push(triple);
-        // }
-
-        path.push(profile);
-        path.into()
+    /// Picks the binary to bundle: the package's `default-run`, its only binary, or the one
+    /// named after the package.
+    fn find_binary_name(package: &cargo_metadata::Package) -> super::Result<String> {
+        if let Some(default_run) = &package.default_run {
+            return Ok(default_run.clone());
+        }
+        let bins: Vec<&str> = package
+            .targets
+            .iter()
+            .filter(|target| target.is_bin())
+            .map(|target| target.name.as_str())
+            .collect();
+        match bins.as_slice() {
+            [bin] => Ok(bin.to_string()),
+            _ if bins.contains(&package.name.as_str()) => Ok(package.name.clone()),
+            [] => bail!("'{}' doesn't have a binary to bundle", package.name),
+            _ => bail!(
+                "'{}' has several binaries ({}), pick one with '--bin'",
+                package.name,
+                bins.join(", ")
+            ),
+        }
     }
 
     /*
@@ -185,12 +173,30 @@ impl Settings {
     ) -> super::Result<(BundleSettings, cargo_metadata::Package)> {
         for package_id in metadata.workspace_members.iter() {
             let package = &metadata[package_id];
+            // Quark.toml takes over from `[package.metadata.bundle]`
+            let quark_toml = package.manifest_path.with_file_name(manifest::FILE_NAME);
+            if quark_toml.exists() {
+                let manifest = match Manifest::load(quark_toml.as_std_path()) {
+                    Ok(manifest) => manifest,
+                    Err(QuarkError::InvalidManifest(e)) => bail!(e),
+                    Err(e) => bail!("{:?}", e),
+                };
+                if package.metadata.get("bundle").is_some() {
+                    print_warning(&format!(
+                        "Ignoring [package.metadata.bundle], {quark_toml} has the bundle settings"
+                    ))?;
+                }
+                let settings = serde_json::from_value(serde_json::to_value(manifest.bundle)?)?;
+                return Ok((settings, package.clone()));
+            }
             if let Some(bundle) = package.metadata.get("bundle") {
                 let settings = serde_json::from_value::<BundleSettings>(bundle.clone())?; // DESERIALIZEEEEEEEEEE
                 return Ok((settings, package.clone()));
             }
         }
-        print_warning("No package in workspace has [package.metadata.bundle] section")?;
+        print_warning(
+            "No package in workspace has a Quark.toml or a [package.metadata.bundle] section",
+        )?;
         if let Some(root_package) = metadata.root_package() {
             Ok((BundleSettings::default(), root_package.clone()))
         } else {
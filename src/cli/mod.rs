@@ -1,66 +1,132 @@
+pub mod build_dev_url;
 pub mod build_http;
 pub mod build_static;
 #[cfg(feature = "bundle")]
 pub mod bundle;
 
-use include_dir::{include_dir, Dir};
-
-static QUARKFOLDER: Dir = include_dir!("$CARGO_MANIFEST_DIR/src_quark");
+/// The environment variables `cargo quark dev` hands its options over with, so they reach
+/// applications that never call [`parse_args`]. Only debug builds read them.
+pub mod env {
+    /// Set to `1` to start the live server.
+    pub const LIVE: &str = "QUARK_LIVE";
+    pub const HOST: &str = "QUARK_HOST";
+    pub const PORT: &str = "QUARK_PORT";
+    pub const DEV_URL: &str = "QUARK_DEV_URL";
+}
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Args {
     pub live: bool,
-    pub bundle: bool,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub dev_url: Option<String>,
+    /// Everything Quark didn't recognize, in order, left for the application to handle.
+    /// Arguments that aren't valid UTF-8 are converted lossily, read [`std::env::args_os`]
+    /// for the exact bytes.
+    pub rest: Vec<String>,
+}
+
+impl Args {
+    /// Hands every argument to the application, Quark's dev flags included. This is what
+    /// [`Quark::new`] uses, see [`parse_args`] to opt into the dev flags.
+    ///
+    /// [`Quark::new`]: crate::Quark::new
+    pub fn from_env() -> Self {
+        Args {
+            rest: args().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Fills in the dev options left unset from the [`env`] variables, in debug builds.
+    pub fn with_dev_env(mut self) -> Self {
+        if !cfg!(debug_assertions) {
+            return self;
+        }
+        let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+        self.live |= var(env::LIVE).is_some_and(|live| live != "0");
+        self.host = self.host.or_else(|| var(env::HOST));
+        self.port = self.port.or_else(|| {
+            var(env::PORT).and_then(|port| {
+                port.parse()
+                    .map_err(|_| log::warn!("Ignoring {}, '{port}' isn't a port.", env::PORT))
+                    .ok()
+            })
+        });
+        self.dev_url = self.dev_url.or_else(|| var(env::DEV_URL));
+        self
+    }
 }
 
+/// The command line without the program name, unlike `std::env::args` it doesn't panic on
+/// arguments that aren't valid UTF-8.
+fn args() -> impl Iterator<Item = String> {
+    std::env::args_os()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+}
+
+/// Returns the value of `flag`, given either as `--flag=value` or as the next argument.
+fn flag_value(
+    flag: &str,
+    inline: Option<String>,
+    args: &mut impl Iterator<Item = String>,
+) -> String {
+    inline.or_else(|| args.next()).unwrap_or_else(|| {
+        eprintln!("'{flag}' needs a value silly. Use '--quark-help' to list the commands.");
+        std::process::exit(1);
+    })
+}
+
+/// Picks Quark's dev flags (`--live`, `--dev-url`...) out of the command line, for use with
+/// [`Quark::with_args`]. Anything else, and everything after `--`, ends up in [`Args::rest`].
+///
+/// [`Quark::with_args`]: crate::Quark::with_args
 pub fn parse_args() -> Args {
     // https://github.com/WilliamAnimate/catgirls_anytime/blob/849c973e8e355cb6ae0695e287764299c6c2543d/src/lib.rs#L18-L76
-    let args: Vec<String> = std::env::args().collect();
+    let mut args = args();
 
     let mut parsed_args = Args::default();
 
-    for args in &args[1..] {
-        match args.as_str() {
-            "--help" => {
-                println!("Usage: cargo run -- [OPTION]");
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_owned(), Some(value.to_owned()))
+            }
+            _ => (arg.clone(), None),
+        };
+        match flag.as_str() {
+            "--" => {
+                parsed_args.rest.extend(args.by_ref());
+            }
+            // `--help` is left to the application
+            "--quark-help" => {
+                println!("Usage: cargo run -- [OPTION]... [-- APP ARGS]");
                 println!("--live          Start a live server with hot reload support.");
-                println!("--bundle        Package your Quark application for your target.\n                You need the `bundle` feature enable.");
-                println!("--help          Display this help message and exit.");
+                println!("--host <HOST>   Address of the live server (default 127.0.0.1).");
+                println!("--port <PORT>   Port of the live server (default 24114).\n                Another one is picked if it's taken.");
+                println!("--dev-url <URL> Load the frontend from an external dev server (e.g. Vite),\n                debug builds only.");
+                println!("--quark-help    Display this help message and exit.");
                 std::process::exit(0);
             }
             "--live" => {
                 parsed_args.live = true;
             }
-            #[cfg(feature = "bundle")]
-            "--bundle" => {
-                parsed_args.bundle = true;
-                use std::path::PathBuf;
-                fn bundle_executable() -> self::bundle::Result<Vec<PathBuf>> {
-                    let current_dir = std::env::current_dir()?;
-                    let settings = self::bundle::Settings::new(current_dir)?;
-                    let bundle_paths = self::bundle::bundle_project(settings)?;
-
-                    Ok(bundle_paths)
-                }
-                match bundle_executable() {
-                    Ok(paths) => {
-                        println!("Successfully bundled application to:");
-                        for path in paths {
-                            println!("  {}", path.display());
-                        }
-                    }
-                    Err(e) => {
-                        let _ = bundle::print_error(&e);
-                        std::process::exit(1);
-                    }
-                }
-                std::process::exit(0);
+            "--host" => {
+                parsed_args.host = Some(flag_value(&flag, inline, &mut args));
+            }
+            "--port" => {
+                let port = flag_value(&flag, inline, &mut args);
+                parsed_args.port = Some(port.parse().unwrap_or_else(|_| {
+                    eprintln!("'{port}' isn't a port silly, it goes from 0 to 65535.");
+                    std::process::exit(1);
+                }));
+            }
+            "--dev-url" => {
+                parsed_args.dev_url = Some(flag_value(&flag, inline, &mut args));
             }
-            other => {
-                eprintln!(
-                    "'{other}' is an unknown argument silly. Use '--help' to list the commands."
-                );
-                std::process::exit(1);
+            _ => {
+                parsed_args.rest.push(arg);
             }
         }
     }
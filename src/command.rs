@@ -0,0 +1,139 @@
+//! Typed commands callable from JavaScript, see [`Quark::command`].
+//!
+//! [`Quark::command`]: crate::Quark::command
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// The status given to `webview_return` to resolve the JavaScript promise.
+pub(crate) const RESOLVE: i32 = 0;
+/// The status given to `webview_return` to reject the JavaScript promise.
+pub(crate) const REJECT: i32 = 1;
+
+/// Decodes the JSON params array of a call to `A`.
+///
+/// Commands that take no arguments use `()`, which is also accepted for an empty array.
+pub fn decode_args<A: DeserializeOwned>(req: &str) -> Result<A, String> {
+    let params: Value = serde_json::from_str(req).map_err(|e| e.to_string())?;
+    let empty = params.as_array().is_some_and(|params| params.is_empty());
+    match serde_json::from_value::<A>(params) {
+        Ok(args) => Ok(args),
+        Err(_) if empty => serde_json::from_value::<A>(Value::Null).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Runs `handler` for a call with the params array `req`, returning the status and the JSON
+/// value to settle the JavaScript promise with.
+pub(crate) fn call<A, R, E, F>(name: &str, handler: &mut F, req: &str) -> (i32, String)
+where
+    A: DeserializeOwned,
+    R: Serialize,
+    E: Serialize,
+    F: FnMut(A) -> Result<R, E>,
+{
+    match decode_args::<A>(req) {
+        Ok(args) => settle(handler(args)),
+        Err(e) => invalid_args(name, &e),
+    }
+}
+
+/// The status and JSON value to reject a call whose arguments couldn't be decoded.
+pub(crate) fn invalid_args(name: &str, error: &str) -> (i32, String) {
+    reject_with(format!("Invalid arguments for '{name}': {error}"))
+}
+
+/// Serializes the result of a command into the status and JSON value to settle the promise with.
+pub(crate) fn settle<R: Serialize, E: Serialize>(result: Result<R, E>) -> (i32, String) {
+    let (status, json) = match result {
+        Ok(value) => (RESOLVE, serde_json::to_string(&value)),
+        Err(error) => (REJECT, serde_json::to_string(&error)),
+    };
+    match json {
+        Ok(json) => (status, json),
+        Err(e) => reject_with(format!("Couldn't serialize the result: {e}")),
+    }
+}
+
+/// The status and JSON value to reject a call whose handler panicked.
+pub(crate) fn panicked(name: &str) -> (i32, String) {
+    reject_with(format!("'{name}' panicked"))
+}
+
+fn reject_with(message: String) -> (i32, String) {
+    (
+        REJECT,
+        serde_json::to_string(&message).unwrap_or_else(|_| String::from("null")),
+    )
+}
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs `job` on the worker pool shared by every async command.
+///
+/// The pool has one thread per core and is only started the first time it's needed. A job that
+/// panics is dropped, the worker keeps going.
+pub(crate) fn spawn<F: FnOnce() + Send + 'static>(job: F) {
+    static POOL: OnceLock<Mutex<Sender<Job>>> = OnceLock::new();
+
+    let pool = POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = thread::available_parallelism().map_or(4, |n| n.get());
+        for i in 0..workers {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("quark-worker-{i}"))
+                .spawn(move || loop {
+                    let job = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => return,
+                    };
+                    match job {
+                        // A panicking job mustn't take its worker down with it
+                        Ok(job) => {
+                            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                        }
+                        Err(_) => return, // the sender is gone, nothing left to do
+                    }
+                })
+                .expect("Failed to spawn a Quark worker thread");
+        }
+        Mutex::new(sender)
+    });
+
+    if let Ok(sender) = pool.lock() {
+        let _ = sender.send(Box::new(job));
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Drives `future` to completion on the current thread, parking it while the future is pending.
+///
+/// This is not an async runtime, futures that need one (e.g. tokio's I/O types) have to be
+/// driven by it, for instance by wrapping them in `Handle::block_on` inside the command.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
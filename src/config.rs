@@ -1,4 +1,9 @@
-use crate::webview::SizeHint;
+use crate::console::{ConsoleHandler, ConsoleMessage};
+use crate::error::QuarkError;
+use crate::frontend::Frontend;
+use crate::navigation::{self, NavigationDecision, NavigationHandler, NavigationRequest};
+use crate::webview::{self, SizeHint};
+use std::rc::Rc;
 
 /// Defines the primary configuration for a Quark application.
 ///
@@ -8,9 +13,11 @@ use crate::webview::SizeHint;
 /// # fn main() -> Result<(), QuarkError> {
 /// let config = QuarkConfig::new()
 ///     .title("Quark!")
+///     .frontend(frontend!())
 ///     .width: 800,
 ///     .height: 600,
-///     .resizable(SizeHint::MIN);
+///     .min_size(400, 300)
+///     .max_size(1600, 1200);
 ///
 /// // QuarkConfig is meant to be used with `Quark::new`
 /// let quark = Quark::new(config)?;
@@ -20,11 +27,26 @@ use crate::webview::SizeHint;
 /// ```
 ///
 /// Also see [`Quark`]
+#[derive(Clone)]
 pub struct QuarkConfig {
     pub(crate) title: String,
     pub(crate) width: usize,
     pub(crate) height: usize,
     pub(crate) resizable: SizeHint,
+    pub(crate) min_size: Option<(usize, usize)>,
+    pub(crate) max_size: Option<(usize, usize)>,
+    pub(crate) lock_aspect_ratio: bool,
+    pub(crate) position: Option<(i32, i32)>,
+    pub(crate) native_script_dialogs: bool,
+    pub(crate) frontend: Option<Frontend>,
+    pub(crate) route: Option<String>,
+    pub(crate) dev_host: String,
+    pub(crate) dev_port: u16,
+    pub(crate) dev_url: Option<String>,
+    pub(crate) devtools: bool,
+    pub(crate) allowed_origins: Vec<String>,
+    pub(crate) navigation_handler: Option<NavigationHandler>,
+    pub(crate) console_handler: Option<ConsoleHandler>,
 }
 
 impl QuarkConfig {
@@ -49,7 +71,7 @@ impl QuarkConfig {
 
     /// Sets the `QuarkConfig.width` value.
     ///
-    /// The `width` value determines the width of the webview should render at.
+    /// The `width` value determines the width of the webview should render at when it opens.
     #[must_use]
     pub fn width(mut self, width: usize) -> Self {
         self.width = width;
@@ -58,7 +80,7 @@ impl QuarkConfig {
 
     /// Sets the `QuarkConfig.height` value.
     ///
-    /// The `height` value determines the height of the webview should render at.
+    /// The `height` value determines the height of the webview should render at when it opens.
     #[must_use]
     pub fn height(mut self, height: usize) -> Self {
         self.height = height;
@@ -68,6 +90,8 @@ impl QuarkConfig {
     /// Sets the `QuarkConfig.resizable` value.
     ///
     /// The `resizable` value determines the resizing conditions for the Quark application.
+    /// With `SizeHint::MIN` or `SizeHint::MAX`, `width` and `height` double as that bound, use
+    /// [`QuarkConfig::min_size`] and [`QuarkConfig::max_size`] to set both.
     ///
     /// Also see [`SizeHint`]
     ///
@@ -78,6 +102,266 @@ impl QuarkConfig {
         self.resizable = resizable;
         self
     }
+
+    /// Sets the `QuarkConfig.min_size` value.
+    ///
+    /// The `min_size` value is the smallest width and height the user can shrink the window
+    /// to. It replaces the bound set by `SizeHint::MIN`. With the default `SizeHint::MAX` the
+    /// size is also the largest one, so a bigger `min_size` needs a `max_size` as well.
+    #[must_use]
+    pub fn min_size(mut self, width: usize, height: usize) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    /// Sets the `QuarkConfig.max_size` value.
+    ///
+    /// The `max_size` value is the largest width and height the user can grow the window to.
+    /// It replaces the bound set by `SizeHint::MAX`.
+    #[must_use]
+    pub fn max_size(mut self, width: usize, height: usize) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    /// Sets the `QuarkConfig.lock_aspect_ratio` value.
+    ///
+    /// The `lock_aspect_ratio` value keeps the window at the proportions of `width` and
+    /// `height` while it's resized.
+    #[must_use]
+    pub fn lock_aspect_ratio(mut self, lock: bool) -> Self {
+        self.lock_aspect_ratio = lock;
+        self
+    }
+
+    /// Sets the `QuarkConfig.position` value.
+    ///
+    /// The `position` value is where the window's top left corner opens on the screen, left to
+    /// the window manager by default. Wayland doesn't let windows place themselves, so it's
+    /// ignored there.
+    #[must_use]
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    /// Sets the `QuarkConfig.native_script_dialogs` value.
+    ///
+    /// The `native_script_dialogs` value shows the page's `alert()`, `confirm()` and
+    /// `prompt()` as native dialogs titled with the window's title, instead of the web-style
+    /// ones titled with the page's URL. It's off by default. macOS doesn't have them yet,
+    /// creating the window fails there with `QuarkError::Unsupported` when it's on.
+    #[must_use]
+    pub fn native_script_dialogs(mut self, native: bool) -> Self {
+        self.native_script_dialogs = native;
+        self
+    }
+
+    /// Sets the `QuarkConfig.frontend` value.
+    ///
+    /// The `frontend` value is the folder holding your HTML, CSS and JavaScript, embedded into
+    /// the binary with the [`frontend!`] macro. `Quark::new` fails with
+    /// `QuarkError::FrontendPathMissing` if this was never set.
+    ///
+    /// [`frontend!`]: crate::frontend!
+    #[must_use]
+    pub fn frontend(mut self, frontend: impl Into<Frontend>) -> Self {
+        self.frontend = Some(frontend.into());
+        self
+    }
+
+    /// Sets the `QuarkConfig.route` value.
+    ///
+    /// The `route` value is the page of the frontend the window opens, such as
+    /// `/preferences.html` or `/#/settings`, `/index.html` by default. With a `dev_url` it's
+    /// added to the end of it.
+    #[must_use]
+    pub fn route(mut self, route: &str) -> Self {
+        self.route = Some(route.to_owned());
+        self
+    }
+
+    /// Sets the `QuarkConfig.dev_host` value.
+    ///
+    /// The `dev_host` value is the address the `--live` server listens on, `127.0.0.1` by
+    /// default. The `--host` argument takes precedence over it.
+    #[must_use]
+    pub fn dev_host(mut self, host: &str) -> Self {
+        self.dev_host = host.to_owned();
+        self
+    }
+
+    /// Sets the `QuarkConfig.dev_port` value.
+    ///
+    /// The `dev_port` value is the port the `--live` server listens on, `24114` by default. If
+    /// it's taken, the server falls back to whatever port the operating system hands out. The
+    /// `--port` argument takes precedence over it.
+    #[must_use]
+    pub fn dev_port(mut self, port: u16) -> Self {
+        self.dev_port = port;
+        self
+    }
+
+    /// Sets the `QuarkConfig.dev_url` value.
+    ///
+    /// The `dev_url` value is the address of an external frontend dev server, such as Vite's
+    /// `http://localhost:5173`, to load the frontend from instead of the embedded folder. It's
+    /// only used in debug builds, release builds always use the embedded folder. The `--dev-url`
+    /// argument takes precedence over it.
+    #[must_use]
+    pub fn dev_url(mut self, url: &str) -> Self {
+        self.dev_url = Some(url.to_owned());
+        self
+    }
+
+    /// Sets the `QuarkConfig.devtools` value.
+    ///
+    /// The `devtools` value enables the web inspector and prints the page's console to stdout
+    /// when no logger takes it, see [`console`]. It's on in debug builds and off in release
+    /// builds by default.
+    ///
+    /// [`console`]: crate::console
+    #[must_use]
+    pub fn devtools(mut self, devtools: bool) -> Self {
+        self.devtools = devtools;
+        self
+    }
+
+    /// Sets the `QuarkConfig.allowed_origins` value.
+    ///
+    /// The `allowed_origins` value lists the origins, such as `https://example.com`, the window
+    /// may navigate to on top of the app's own. `https://*.example.com` takes in every
+    /// subdomain. Links elsewhere open in the user's browser, see [`navigation`].
+    ///
+    /// macOS doesn't check navigations yet, creating the window fails there with
+    /// `QuarkError::Unsupported` when origins are given.
+    ///
+    /// [`navigation`]: crate::navigation
+    #[must_use]
+    pub fn allowed_origins(mut self, origins: &[&str]) -> Self {
+        self.allowed_origins = origins.iter().map(|origin| origin.to_string()).collect();
+        self
+    }
+
+    /// Sets the `QuarkConfig.navigation_handler` value.
+    ///
+    /// The `navigation_handler` value decides where the window may go when it's neither the
+    /// app's origin nor one of the `allowed_origins`, instead of opening the user's links in
+    /// their browser and denying the rest. Like `allowed_origins`, it makes creating the window
+    /// fail with `QuarkError::Unsupported` on macOS.
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// let config = QuarkConfig::new().on_navigation(|request| {
+    ///     if request.url.starts_with("https://docs.example.com/") {
+    ///         NavigationDecision::Allow
+    ///     } else {
+    ///         NavigationDecision::OpenExternally
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn on_navigation<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&NavigationRequest) -> NavigationDecision + 'static,
+    {
+        self.navigation_handler = Some(Rc::new(handler));
+        self
+    }
+
+    /// Sets the `QuarkConfig.console_handler` value.
+    ///
+    /// The `console_handler` value gets the page's console output, uncaught errors and
+    /// unhandled promise rejections, instead of the [`log`] crate. See [`console`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// let config = QuarkConfig::new().on_console(|message| {
+    ///     if message.level == log::Level::Error {
+    ///         crash_report.push(message.to_string());
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [`console`]: crate::console
+    #[must_use]
+    pub fn on_console<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&ConsoleMessage) + 'static,
+    {
+        self.console_handler = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl QuarkConfig {
+    /// Checks the window's sizes fit the platform and each other, failing with
+    /// `QuarkError::InvalidWindowSize`.
+    pub(crate) fn validate_size(&self) -> Result<(), QuarkError> {
+        let sizes = [
+            ("size", Some((self.width, self.height))),
+            ("min_size", self.min_size),
+            ("max_size", self.max_size),
+        ];
+        for (name, size) in sizes {
+            if let Some((width, height)) = size {
+                let fits = |length: usize| (1..=u16::MAX as usize).contains(&length);
+                if !fits(width) || !fits(height) {
+                    return Err(QuarkError::InvalidWindowSize(format!(
+                        "{name} is {width}x{height}, both have to be between 1 and {}",
+                        u16::MAX
+                    )));
+                }
+            }
+        }
+
+        // A MIN or MAX hint makes `width` and `height` that bound, unless it's set explicitly
+        let size = (self.width, self.height);
+        let bound = |explicit: Option<(usize, usize)>, name: &str, hint| match explicit {
+            Some(bound) => Some((bound, name.to_owned())),
+            None => (self.resizable == hint).then(|| (size, format!("the size (with {hint:?})"))),
+        };
+        let min = bound(self.min_size, "min_size", SizeHint::MIN);
+        let max = bound(self.max_size, "max_size", SizeHint::MAX);
+        if let (Some((min, min_name)), Some((max, max_name))) = (min, max) {
+            if min.0 > max.0 || min.1 > max.1 {
+                return Err(QuarkError::InvalidWindowSize(format!(
+                    "{min_name} ({}x{}) is bigger than {max_name} ({}x{})",
+                    min.0, min.1, max.0, max.1
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the platform has what the config asks for, failing with
+    /// `QuarkError::Unsupported`. macOS doesn't enforce the default navigation policy either,
+    /// but failing every window there would be worse, so that's only a warning.
+    pub(crate) fn validate_platform(&self) -> Result<(), QuarkError> {
+        if self.native_script_dialogs {
+            webview::supported("native script dialogs")?;
+        }
+        if !self.allowed_origins.is_empty() || self.navigation_handler.is_some() {
+            webview::supported(navigation::POLICIES)?;
+        }
+        if let Err(e) = webview::supported(navigation::POLICIES) {
+            log::warn!("{e}. The window may go anywhere.");
+        }
+        Ok(())
+    }
+}
+
+impl QuarkConfig {
+    /// Returns the route to open, starting with a `/`.
+    pub(crate) fn route_or_index(&self) -> String {
+        match self.route.as_deref() {
+            Some(route) if route.starts_with('/') => route.to_owned(),
+            Some(route) => format!("/{route}"),
+            None => String::from("/index.html"),
+        }
+    }
 }
 
 impl Default for QuarkConfig {
@@ -87,6 +371,20 @@ impl Default for QuarkConfig {
             width: 800,
             height: 600,
             resizable: SizeHint::MAX,
+            min_size: None,
+            max_size: None,
+            lock_aspect_ratio: false,
+            position: None,
+            native_script_dialogs: false,
+            frontend: None,
+            route: None,
+            dev_host: String::from("127.0.0.1"),
+            dev_port: 24114,
+            dev_url: None,
+            devtools: cfg!(debug_assertions),
+            allowed_origins: Vec::new(),
+            navigation_handler: None,
+            console_handler: None,
         }
     }
 }
@@ -0,0 +1,167 @@
+//! The page's console output and uncaught errors, forwarded to Rust in debug and release
+//! builds alike, see [`QuarkConfig::on_console`].
+//!
+//! By default they go to the [`log`] crate under the `libquark::console` target, at the level
+//! matching the console method: `console.error` and uncaught errors at `Error`,
+//! `console.warn` at `Warn`, `console.log` and `console.info` at `Info`, `console.debug` at
+//! `Debug` and `console.trace` at `Trace`. When no logger takes them and the devtools are on,
+//! they're printed to stdout instead.
+//!
+//! [`QuarkConfig::on_console`]: crate::config::QuarkConfig::on_console
+
+use crate::command;
+use crate::webview::Webview;
+use log::Level;
+use serde::Deserialize;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// The binding the page's console goes through.
+const CONSOLE_BINDING: &str = "__quark_console";
+
+/// Injected into every page, before any of its own scripts run.
+const SCRIPT: &str = r#"(function() {
+  // The binding is only there once its own script has run
+  var send = function(report) {
+    try { window.__quark_console(report); } catch (e) {}
+  };
+  var format = function(value) {
+    if (typeof value === "string") { return value; }
+    if (value instanceof Error) { return value.stack ? value + "\n" + value.stack : String(value); }
+    try {
+      var json = JSON.stringify(value);
+      return json === undefined ? String(value) : json;
+    } catch (e) {
+      return String(value);
+    }
+  };
+  // The frames of a WebKit stack look like `name@url:line:column`
+  var location = function(stack, frame) {
+    var match = /([^@]*):(\d+):(\d+)$/.exec((stack || "").split("\n")[frame] || "");
+    return match ? { source: match[1], line: +match[2], column: +match[3] } : {};
+  };
+  var report = function(level, kind, message, where) {
+    send({
+      level: level,
+      kind: kind,
+      message: message,
+      source: where.source || null,
+      line: where.line || null,
+      column: where.column || null
+    });
+  };
+  var levels = { error: "error", warn: "warn", log: "info", info: "info", debug: "debug", trace: "trace" };
+  Object.keys(levels).forEach(function(method) {
+    var original = console[method];
+    console[method] = function() {
+      // 0 is this function, 1 whoever called console
+      var where = location(new Error().stack, 1);
+      report(levels[method], "console", Array.prototype.map.call(arguments, format).join(" "), where);
+      return original.apply(console, arguments);
+    };
+  });
+  window.addEventListener("error", function(event) {
+    var message = event.error instanceof Error ? format(event.error) : event.message;
+    report("error", "uncaughtError", message, {
+      source: event.filename, line: event.lineno, column: event.colno
+    });
+  });
+  window.addEventListener("unhandledrejection", function(event) {
+    var reason = event.reason;
+    var where = reason instanceof Error ? location(reason.stack, 0) : {};
+    report("error", "unhandledRejection", format(reason), where);
+  });
+})();"#;
+
+/// Where a [`ConsoleMessage`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConsoleKind {
+    /// A call to `console.log`, `console.error`, ...
+    Console,
+    /// An exception nothing caught
+    UncaughtError,
+    /// A rejected promise nothing handled
+    UnhandledRejection,
+}
+
+/// Something the page printed or threw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleMessage {
+    pub level: Level,
+    pub kind: ConsoleKind,
+    /// The arguments of the console call joined with spaces, objects as JSON and errors with
+    /// their stack
+    pub message: String,
+    /// The URL of the script, if it's known
+    pub source: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+impl fmt::Display for ConsoleMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ConsoleKind::Console => write!(f, "{}", self.message)?,
+            ConsoleKind::UncaughtError => write!(f, "Uncaught {}", self.message)?,
+            ConsoleKind::UnhandledRejection => write!(f, "Unhandled rejection: {}", self.message)?,
+        }
+        if let Some(source) = &self.source {
+            write!(f, " ({source}")?;
+            if let Some(line) = self.line {
+                write!(f, ":{line}")?;
+            }
+            if let Some(column) = self.column {
+                write!(f, ":{column}")?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// What the page sends, the level being one of `log`'s in lowercase.
+#[derive(Deserialize)]
+struct Report {
+    level: String,
+    kind: ConsoleKind,
+    message: String,
+    source: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+}
+
+pub(crate) type ConsoleHandler = Rc<dyn Fn(&ConsoleMessage)>;
+
+/// Forwards the page's console to `handler`, or to `log` without one, printing it when no
+/// logger takes it and `echo` is set.
+pub(crate) fn install(webview: &mut Webview, handler: Option<ConsoleHandler>, echo: bool) {
+    webview.init(SCRIPT);
+
+    let returner = webview.clone();
+    webview.bind(CONSOLE_BINDING, move |seq, req| {
+        let report = match command::decode_args::<(Report,)>(req) {
+            Ok((report,)) => report,
+            Err(e) => {
+                let (status, result) = command::invalid_args("console", &e);
+                return returner.r#return(seq, status, &result);
+            }
+        };
+        let message = ConsoleMessage {
+            level: Level::from_str(&report.level).unwrap_or(Level::Info),
+            kind: report.kind,
+            message: report.message,
+            source: report.source,
+            line: report.line,
+            column: report.column,
+        };
+        match &handler {
+            Some(handler) => handler(&message),
+            None if log::log_enabled!(message.level) => log::log!(message.level, "{message}"),
+            None if echo => println!("[{}] {message}", message.level),
+            None => {}
+        }
+        returner.r#return(seq, command::RESOLVE, "null");
+    });
+}
@@ -0,0 +1,462 @@
+//! Native dialogs, see [`FileDialog`] and [`MessageDialog`].
+//!
+//! Every page gets a `quark.dialog` object. Its file dialogs resolve with the picked paths, or
+//! with `null` (an empty array with `multiple`) when the user cancels:
+//!
+//! ```js
+//! const project = await quark.dialog.pickFolder({ title: "Open project" });
+//! const images = await quark.dialog.open({
+//!   multiple: true,
+//!   filters: [{ name: "Images", extensions: ["png", "jpg"] }],
+//! });
+//! const target = await quark.dialog.save({ defaultName: "export.csv", defaultFolder: project });
+//! ```
+//!
+//! The options are the same as [`FileDialog`]'s: `title`, `filters`, `defaultName` and
+//! `defaultFolder`, along with `multiple` for `open` and `pickFolder`.
+//!
+//! Message dialogs take [`MessageDialog`]'s options, `message` resolving with a
+//! [`MessageResponse`]. `alert`, `confirm` and `prompt` are shortcuts working like the
+//! browser's, but asynchronous:
+//!
+//! ```js
+//! const { button, checked } = await quark.dialog.message({
+//!   level: "warning",
+//!   message: "Discard the changes?",
+//!   buttons: ["Discard", "Keep editing"],
+//!   checkbox: "Don't ask again",
+//! });
+//! if (await quark.dialog.confirm("Delete the project?")) { /* ... */ }
+//! const name = await quark.dialog.prompt("Name of the copy", "Untitled", { title: "Copy" });
+//! ```
+//!
+//! The page's own `alert()`, `confirm()` and `prompt()` can be shown natively too, see
+//! [`QuarkConfig::native_script_dialogs`].
+//!
+//! macOS doesn't have the dialogs yet. Showing one fails there with `QuarkError::Unsupported`,
+//! and the promises of `quark.dialog` reject with its message.
+//!
+//! [`QuarkConfig::native_script_dialogs`]: crate::config::QuarkConfig::native_script_dialogs
+
+use crate::command;
+use crate::error::QuarkError;
+use crate::webview::{self, FileDialogAction, MessageLevel, MessageResponse, Webview};
+use crate::Quark;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// The binding `quark.dialog` calls through.
+const DIALOG_BINDING: &str = "__quark_dialog";
+
+/// What `QuarkError::Unsupported` names on the platforms missing them.
+pub(crate) const FILE_DIALOGS: &str = "file dialogs";
+pub(crate) const MESSAGE_DIALOGS: &str = "message dialogs";
+
+/// Injected into every page, before any of its own scripts run.
+const SCRIPT: &str = r#"(function() {
+  var quark = window.quark = window.quark || {};
+  var call = function(op, options) {
+    return window.__quark_dialog({ op: op, arg: options || {} });
+  };
+  var ask = function(message, before, after) {
+    return call("message", Object.assign({ level: "question" }, before, after, {
+      message: String(message)
+    }));
+  };
+  quark.dialog = {
+    open: function(options) { return call("open", options); },
+    save: function(options) { return call("save", options); },
+    pickFolder: function(options) { return call("pickFolder", options); },
+    message: function(options) { return call("message", options); },
+    alert: function(message, options) {
+      return ask(message, { level: "info" }, options).then(function() {});
+    },
+    confirm: function(message, options) {
+      return ask(message, options).then(function(response) {
+        return response.button === 0;
+      });
+    },
+    prompt: function(message, defaultText, options) {
+      return ask(message, options, { input: defaultText == null ? "" : String(defaultText) })
+        .then(function(response) { return response.button === 0 ? response.input : null; });
+    }
+  };
+})();"#;
+
+#[derive(Deserialize)]
+struct Options {
+    #[serde(flatten)]
+    dialog: FileDialog,
+    #[serde(default)]
+    multiple: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "op", content = "arg", rename_all = "camelCase")]
+enum Op {
+    Open(Options),
+    Save(Options),
+    PickFolder(Options),
+    Message(MessageDialog),
+}
+
+/// What a [`FileDialog`] asks the user for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileDialogMode {
+    PickFile,
+    PickFiles,
+    PickFolder,
+    PickFolders,
+    SaveFile,
+}
+
+/// A group of files a [`FileDialog`] can be narrowed down to.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FileFilter {
+    /// What the user sees, e.g. `Images`
+    pub name: String,
+    /// Without the dot, e.g. `png`, matched whatever the case. `*` matches every file.
+    pub extensions: Vec<String>,
+}
+
+/// A native dialog to open files or folders, or to pick where to save a file. It's modal to
+/// the window, its methods return right away and call back once the user is done. They fail
+/// with `QuarkError::Unsupported` on macOS, where there's no file dialog yet.
+///
+/// # Examples
+///
+/// ```rust, ignore
+/// let dialog = FileDialog::new()
+///     .title("Export as")
+///     .filter("CSV", &["csv"])
+///     .default_name("export.csv");
+/// dialog.save_file(&mut quark, move |path| {
+///     if let Some(path) = path {
+///         let _ = std::fs::write(path, csv);
+///     }
+/// })?;
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FileDialog {
+    title: Option<String>,
+    filters: Vec<FileFilter>,
+    default_name: Option<String>,
+    default_folder: Option<PathBuf>,
+}
+
+impl FileDialog {
+    /// Creates a dialog with the platform's title and no filter.
+    pub fn new() -> Self {
+        FileDialog::default()
+    }
+
+    /// Sets the title of the dialog.
+    #[must_use]
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    /// Adds a filter called `name`, showing the files with one of `extensions`. The first one
+    /// added is picked when the dialog opens.
+    #[must_use]
+    pub fn filter(mut self, name: &str, extensions: &[&str]) -> Self {
+        self.filters.push(FileFilter {
+            name: name.to_owned(),
+            extensions: extensions.iter().map(|e| e.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Sets the file name a save dialog suggests.
+    #[must_use]
+    pub fn default_name(mut self, name: &str) -> Self {
+        self.default_name = Some(name.to_owned());
+        self
+    }
+
+    /// Sets the folder the dialog opens in.
+    #[must_use]
+    pub fn default_folder(mut self, folder: impl AsRef<Path>) -> Self {
+        self.default_folder = Some(folder.as_ref().to_owned());
+        self
+    }
+
+    /// Asks for an existing file, then calls `then` with it, `None` if the user cancelled.
+    pub fn pick_file<F>(&self, quark: &mut Quark, then: F) -> Result<(), QuarkError>
+    where
+        F: FnOnce(Option<PathBuf>) + 'static,
+    {
+        self.show(&mut quark.webview, FileDialogMode::PickFile, |mut paths| {
+            then(paths.pop())
+        })
+    }
+
+    /// Asks for existing files, then calls `then` with them, none if the user cancelled.
+    pub fn pick_files<F>(&self, quark: &mut Quark, then: F) -> Result<(), QuarkError>
+    where
+        F: FnOnce(Vec<PathBuf>) + 'static,
+    {
+        self.show(&mut quark.webview, FileDialogMode::PickFiles, then)
+    }
+
+    /// Asks for an existing folder, then calls `then` with it, `None` if the user cancelled.
+    pub fn pick_folder<F>(&self, quark: &mut Quark, then: F) -> Result<(), QuarkError>
+    where
+        F: FnOnce(Option<PathBuf>) + 'static,
+    {
+        self.show(
+            &mut quark.webview,
+            FileDialogMode::PickFolder,
+            |mut paths| then(paths.pop()),
+        )
+    }
+
+    /// Asks for existing folders, then calls `then` with them, none if the user cancelled.
+    pub fn pick_folders<F>(&self, quark: &mut Quark, then: F) -> Result<(), QuarkError>
+    where
+        F: FnOnce(Vec<PathBuf>) + 'static,
+    {
+        self.show(&mut quark.webview, FileDialogMode::PickFolders, then)
+    }
+
+    /// Asks where to save a file, then calls `then` with it, `None` if the user cancelled. The
+    /// user confirms before overwriting a file.
+    pub fn save_file<F>(&self, quark: &mut Quark, then: F) -> Result<(), QuarkError>
+    where
+        F: FnOnce(Option<PathBuf>) + 'static,
+    {
+        self.show(&mut quark.webview, FileDialogMode::SaveFile, |mut paths| {
+            then(paths.pop())
+        })
+    }
+
+    /// Shows the dialog over `webview`, then calls `then` with the picked paths.
+    pub(crate) fn show<F>(
+        &self,
+        webview: &mut Webview,
+        mode: FileDialogMode,
+        then: F,
+    ) -> Result<(), QuarkError>
+    where
+        F: FnOnce(Vec<PathBuf>) + 'static,
+    {
+        webview::supported(FILE_DIALOGS)?;
+        let (action, multiple) = match mode {
+            FileDialogMode::PickFile => (FileDialogAction::Open, false),
+            FileDialogMode::PickFiles => (FileDialogAction::Open, true),
+            FileDialogMode::PickFolder => (FileDialogAction::Folder, false),
+            FileDialogMode::PickFolders => (FileDialogAction::Folder, true),
+            FileDialogMode::SaveFile => (FileDialogAction::Save, false),
+        };
+        let patterns: Vec<String> = self
+            .filters
+            .iter()
+            .map(|filter| {
+                let patterns: Vec<String> = filter.extensions.iter().map(|e| pattern(e)).collect();
+                patterns.join(";")
+            })
+            .collect();
+        let filters: Vec<(&str, &str)> = self
+            .filters
+            .iter()
+            .zip(&patterns)
+            .map(|(filter, patterns)| (filter.name.as_str(), patterns.as_str()))
+            .collect();
+
+        webview.file_dialog(
+            action,
+            multiple,
+            self.title.as_deref(),
+            self.default_name.as_deref(),
+            self.default_folder.as_deref(),
+            &filters,
+            |paths| then(paths.unwrap_or_default()),
+        );
+        Ok(())
+    }
+}
+
+/// A native message dialog, modal to the window. Showing it returns right away and calls back
+/// once the user is done, or fails with `QuarkError::Unsupported` on macOS, which doesn't have
+/// message dialogs yet.
+///
+/// # Examples
+///
+/// ```rust, ignore
+/// MessageDialog::new(MessageLevel::Warning, "Discard the changes?")
+///     .buttons(&["Discard", "Keep editing"])
+///     .checkbox("Don't ask again", false)
+///     .show(&mut quark, |response| {
+///         if response.button == Some(0) {
+///             discard();
+///         }
+///     })?;
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MessageDialog {
+    level: MessageLevel,
+    title: Option<String>,
+    message: String,
+    buttons: Vec<String>,
+    checkbox: Option<String>,
+    checked: bool,
+    input: Option<String>,
+}
+
+impl MessageDialog {
+    /// Creates a dialog showing `message`, with an OK button (and a Cancel one for questions).
+    pub fn new(level: MessageLevel, message: &str) -> Self {
+        MessageDialog {
+            level,
+            message: message.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the title of the dialog.
+    #[must_use]
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_owned());
+        self
+    }
+
+    /// Replaces the OK and Cancel buttons with `buttons`, the first one being the default.
+    /// [`MessageResponse::button`] is the index of the one clicked.
+    #[must_use]
+    pub fn buttons(mut self, buttons: &[&str]) -> Self {
+        self.buttons = buttons.iter().map(|button| button.to_string()).collect();
+        self
+    }
+
+    /// Adds a checkbox labelled `label` below the message, e.g. "Don't ask again".
+    #[must_use]
+    pub fn checkbox(mut self, label: &str, checked: bool) -> Self {
+        self.checkbox = Some(label.to_owned());
+        self.checked = checked;
+        self
+    }
+
+    /// Adds a text field holding `text` below the message, what the user types in is
+    /// [`MessageResponse::input`].
+    #[must_use]
+    pub fn input(mut self, text: &str) -> Self {
+        self.input = Some(text.to_owned());
+        self
+    }
+
+    /// Shows the dialog, then calls `then` with the user's answer.
+    pub fn show<F>(&self, quark: &mut Quark, then: F) -> Result<(), QuarkError>
+    where
+        F: FnOnce(MessageResponse) + 'static,
+    {
+        self.run(&mut quark.webview, then)
+    }
+
+    /// Shows the dialog over `webview`, then calls `then` with the user's answer.
+    pub(crate) fn run<F>(&self, webview: &mut Webview, then: F) -> Result<(), QuarkError>
+    where
+        F: FnOnce(MessageResponse) + 'static,
+    {
+        webview::supported(MESSAGE_DIALOGS)?;
+        let buttons: Vec<&str> = self.buttons.iter().map(String::as_str).collect();
+        webview.message_dialog(
+            self.level,
+            self.title.as_deref(),
+            &self.message,
+            &buttons,
+            self.checkbox.as_deref().map(|label| (label, self.checked)),
+            self.input.as_deref(),
+            then,
+        );
+        Ok(())
+    }
+}
+
+/// Turns an extension into a glob matching it whatever the case, `png` into `*.[pP][nN][gG]`.
+fn pattern(extension: &str) -> String {
+    let extension = extension.trim_start_matches("*.").trim_start_matches('.');
+    if extension == "*" {
+        return String::from("*");
+    }
+    let mut pattern = String::from("*.");
+    for c in extension.chars() {
+        match c {
+            c if c.is_alphabetic() => {
+                pattern.push('[');
+                pattern.extend(c.to_lowercase());
+                pattern.extend(c.to_uppercase());
+                pattern.push(']');
+            }
+            // Glob characters are matched as they are
+            '[' | ']' | '*' | '?' => {
+                pattern.push('[');
+                pattern.push(c);
+                pattern.push(']');
+            }
+            c => pattern.push(c),
+        }
+    }
+    pattern
+}
+
+/// Injects `quark.dialog` into `webview`.
+pub(crate) fn install(webview: &mut Webview) {
+    webview.init(SCRIPT);
+
+    let mut window = webview.clone();
+    webview.bind(DIALOG_BINDING, move |seq, req| {
+        let op = match command::decode_args::<(Op,)>(req) {
+            Ok((op,)) => op,
+            Err(e) => {
+                let (status, result) = command::invalid_args("quark.dialog", &e);
+                return window.r#return(seq, status, &result);
+            }
+        };
+        // Settled once the user is done, the main loop keeps running meanwhile
+        let returner = window.clone();
+        let pending = seq.to_owned();
+        let shown = apply(&mut window, op, move |value| {
+            let (status, result) = command::settle(Ok::<_, ()>(value));
+            returner.r#return(&pending, status, &result);
+        });
+        if let Err(e) = shown {
+            let (status, result) = command::settle(Err::<(), _>(e.to_string()));
+            window.r#return(seq, status, &result);
+        }
+    });
+}
+
+/// Shows the dialog `op` asks for, then calls `then` with what the promise resolves with.
+fn apply<F>(window: &mut Webview, op: Op, then: F) -> Result<(), QuarkError>
+where
+    F: FnOnce(Value) + 'static,
+{
+    let (options, mode) = match op {
+        // A plain struct of strings, numbers and booleans always serializes
+        Op::Message(dialog) => {
+            return dialog.run(window, |response| {
+                then(serde_json::to_value(response).unwrap_or_default())
+            })
+        }
+        Op::Open(options) if options.multiple => (options, FileDialogMode::PickFiles),
+        Op::Open(options) => (options, FileDialogMode::PickFile),
+        Op::PickFolder(options) if options.multiple => (options, FileDialogMode::PickFolders),
+        Op::PickFolder(options) => (options, FileDialogMode::PickFolder),
+        Op::Save(options) => (options, FileDialogMode::SaveFile),
+    };
+    options.dialog.show(window, mode, move |paths| {
+        // Paths that aren't UTF-8 get replacement characters, JSON has no other way
+        let mut paths: Vec<String> = paths
+            .iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        then(match mode {
+            FileDialogMode::PickFiles | FileDialogMode::PickFolders => json!(paths),
+            _ => json!(paths.pop()),
+        });
+    })
+}
@@ -1,7 +1,42 @@
+use std::fmt;
+
 #[derive(Debug)]
 pub enum QuarkError {
     FrontendPathMissing,
+    FileWatcherError,
     IncludeDirCouldntConvertToUTF8,
+    InvalidUrl(String),
+    InvalidManifest(String),
+    InvalidWindowSize(String),
+    WindowAlreadyOpen(String),
+    JavaScriptError(String),
+    Unsupported(String),
     ServerPortIsntAvailable,
     ServerError,
 }
+
+impl fmt::Display for QuarkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuarkError::FrontendPathMissing => write!(f, "No frontend was given"),
+            QuarkError::FileWatcherError => write!(f, "Couldn't watch the frontend folder"),
+            QuarkError::IncludeDirCouldntConvertToUTF8 => {
+                write!(f, "A frontend file isn't valid UTF-8")
+            }
+            QuarkError::InvalidUrl(url) => write!(f, "'{url}' isn't a valid URL"),
+            QuarkError::InvalidManifest(e) => write!(f, "Invalid manifest, {e}"),
+            QuarkError::InvalidWindowSize(e) => write!(f, "Invalid window size, {e}"),
+            QuarkError::WindowAlreadyOpen(label) => write!(f, "The '{label}' window is open"),
+            QuarkError::JavaScriptError(e) => write!(f, "{e}"),
+            QuarkError::Unsupported(feature) => {
+                write!(f, "Not supported on this platform yet: {feature}")
+            }
+            QuarkError::ServerPortIsntAvailable => {
+                write!(f, "The live server couldn't get a port")
+            }
+            QuarkError::ServerError => write!(f, "The live server failed"),
+        }
+    }
+}
+
+impl std::error::Error for QuarkError {}
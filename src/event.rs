@@ -0,0 +1,117 @@
+//! The event bus between Rust and JavaScript, see [`Quark::emit`] and [`Quark::listen`].
+//!
+//! Every page gets a `window.quark` object to talk to it:
+//!
+//! ```js
+//! const off = quark.on("progress", (percent) => console.log(percent));
+//! quark.emit("save", { path: "notes.txt" });
+//! off();
+//! ```
+//!
+//! [`Quark::emit`]: crate::Quark::emit
+//! [`Quark::listen`]: crate::Quark::listen
+
+use crate::command;
+use crate::webview::Webview;
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The binding JavaScript sends its events through.
+const EMIT_BINDING: &str = "__quark_emit";
+
+/// Injected into every page, before any of its own scripts run.
+const SCRIPT: &str = r#"(function() {
+  var listeners = {};
+  var quark = window.quark = window.quark || {};
+  quark.on = function(event, handler) {
+    (listeners[event] = listeners[event] || []).push(handler);
+    return function() { quark.off(event, handler); };
+  };
+  quark.once = function(event, handler) {
+    var off = quark.on(event, function(payload) { off(); handler(payload); });
+    return off;
+  };
+  quark.off = function(event, handler) {
+    var handlers = listeners[event] || [];
+    var i = handlers.indexOf(handler);
+    if (i !== -1) { handlers.splice(i, 1); }
+  };
+  quark.emit = function(event, payload) {
+    return window.__quark_emit(event, payload === undefined ? null : payload);
+  };
+  quark.__dispatch = function(event, payload) {
+    (listeners[event] || []).slice().forEach(function(handler) {
+      try { handler(payload); } catch (e) { console.error(e); }
+    });
+  };
+})();"#;
+
+type Listener = Box<dyn FnMut(&Value)>;
+
+/// The Rust listeners of a window, by event name.
+#[derive(Clone, Default)]
+pub(crate) struct Listeners(Rc<RefCell<HashMap<String, Vec<Listener>>>>);
+
+impl Listeners {
+    pub(crate) fn add(&self, event: &str, listener: Listener) {
+        self.0
+            .borrow_mut()
+            .entry(event.to_owned())
+            .or_default()
+            .push(listener);
+    }
+
+    fn call(&self, event: &str, payload: &Value) {
+        // Taken out while they run, so they can listen and emit themselves
+        let Some(mut listeners) = self.0.borrow_mut().remove(event) else {
+            return;
+        };
+        for listener in listeners.iter_mut() {
+            listener(payload);
+        }
+        let mut all = self.0.borrow_mut();
+        let added = all.entry(event.to_owned()).or_default();
+        listeners.append(added);
+        *added = listeners;
+    }
+}
+
+/// Injects `window.quark` into `webview` and routes the events it emits to `listeners`.
+pub(crate) fn install(webview: &mut Webview, listeners: Listeners) {
+    webview.init(SCRIPT);
+
+    let returner = webview.clone();
+    webview.bind(EMIT_BINDING, move |seq, req| {
+        match command::decode_args::<(String, Value)>(req) {
+            Ok((event, payload)) => {
+                listeners.call(&event, &payload);
+                returner.r#return(seq, command::RESOLVE, "null");
+            }
+            Err(e) => {
+                let (status, result) = command::invalid_args("quark.emit", &e);
+                returner.r#return(seq, status, &result);
+            }
+        }
+    });
+}
+
+/// Makes the application's command line arguments readable from `window.quark.args`.
+pub(crate) fn expose_args(webview: &mut Webview, args: &[String]) {
+    // A list of strings always serializes
+    let args = serde_json::to_string(args).unwrap_or_else(|_| "[]".to_owned());
+    webview.init(&format!(
+        "(window.quark = window.quark || {{}}).args = Object.freeze({args});"
+    ));
+}
+
+/// Returns the JavaScript that hands `payload` to the page's listeners of `event`.
+pub(crate) fn emit_js<T: Serialize>(event: &str, payload: &T) -> serde_json::Result<String> {
+    Ok(format!(
+        "window.quark && window.quark.__dispatch({}, {});",
+        serde_json::to_string(event)?,
+        serde_json::to_string(payload)?
+    ))
+}
@@ -0,0 +1,81 @@
+use include_dir::Dir;
+use std::path::{Path, PathBuf};
+
+/// The frontend folder of a Quark application, embedded into the binary at compile time.
+///
+/// You usually don't build this yourself, use the [`frontend!`] macro instead so the folder is
+/// resolved against *your* crate and not libquark's.
+///
+/// [`frontend!`]: crate::frontend!
+#[derive(Clone)]
+pub struct Frontend {
+    pub(crate) dir: Dir<'static>,
+    pub(crate) source: Option<PathBuf>,
+}
+
+impl Frontend {
+    /// Used by [`frontend!`], `path` being the literal given to it and `manifest_dir` the
+    /// calling crate's `CARGO_MANIFEST_DIR`.
+    ///
+    /// [`frontend!`]: crate::frontend!
+    #[doc(hidden)]
+    pub fn __embed(dir: Dir<'static>, path: &str, manifest_dir: &str) -> Self {
+        let source = path
+            .replace("${CARGO_MANIFEST_DIR}", manifest_dir)
+            .replace("$CARGO_MANIFEST_DIR", manifest_dir);
+        Frontend {
+            dir,
+            // Other variables were only known to the compiler, so there's no telling where it is
+            source: (!source.contains('$')).then(|| PathBuf::from(source)),
+        }
+    }
+
+    /// Returns the embedded directory.
+    pub fn dir(&self) -> &Dir<'static> {
+        &self.dir
+    }
+
+    /// Returns where the folder was embedded from, if it's known. The live server serves the
+    /// files from there so edits show up without a rebuild.
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+}
+
+impl From<Dir<'static>> for Frontend {
+    fn from(dir: Dir<'static>) -> Self {
+        Frontend { dir, source: None }
+    }
+}
+
+/// Embeds the calling crate's frontend folder into the binary.
+///
+/// With no arguments the `src_quark` folder next to your `Cargo.toml` is used. Any path given is
+/// passed straight to [`include_dir!`], so environment variables such as `$CARGO_MANIFEST_DIR`
+/// are expanded at compile time, in the crate calling the macro.
+///
+/// # Examples
+///
+/// ```rust, ignore
+/// let config = QuarkConfig::new()
+///     .title("Quark!")
+///     .frontend(frontend!("$CARGO_MANIFEST_DIR/ui"));
+/// ```
+///
+/// [`include_dir!`]: include_dir::include_dir
+#[macro_export]
+macro_rules! frontend {
+    () => {
+        $crate::frontend!("$CARGO_MANIFEST_DIR/src_quark")
+    };
+    ($path:tt) => {{
+        // `include_dir!` expands to paths starting with `include_dir::`, this makes them resolve
+        // without the application having to depend on the crate itself.
+        use $crate::include_dir;
+        $crate::frontend::Frontend::__embed(
+            include_dir::include_dir!($path),
+            $path,
+            env!("CARGO_MANIFEST_DIR"),
+        )
+    }};
+}
@@ -0,0 +1,369 @@
+use crate::dialog::{FileDialog, FileDialogMode, MessageDialog};
+use crate::error::QuarkError;
+use crate::event;
+use crate::url;
+use crate::webview::{Dispatcher, MessageResponse, SizeHint};
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, ThreadId};
+
+/// A cloneable, thread-safe handle for driving a Quark window from other threads.
+///
+/// Every method posts its work onto the UI thread and returns right away. Once the window's
+/// main loop has ended (see `Quark::run`) the handle does nothing.
+///
+/// # Examples
+///
+/// ```rust, ignore
+/// let handle = quark.handle();
+/// std::thread::spawn(move || {
+///     handle.set_title("Working...");
+///     handle.emit("progress", 50);
+/// });
+/// quark.run();
+/// ```
+#[derive(Clone)]
+pub struct QuarkHandle {
+    shared: Arc<Shared>,
+}
+
+pub(crate) struct Shared {
+    dispatcher: Dispatcher,
+    /// Locked across the check and the enqueue of every dispatch, so nothing gets queued once
+    /// `stop` has returned
+    running: Mutex<bool>,
+    ui_thread: ThreadId,
+    /// The slots of the [`EvalFuture`]s still waiting, their jobs may never run if the loop
+    /// ends first
+    pending: Mutex<Vec<Weak<Mutex<Slot>>>>,
+}
+
+impl Shared {
+    /// Called on the UI thread.
+    pub(crate) fn new(dispatcher: Dispatcher) -> Arc<Self> {
+        Arc::new(Shared {
+            dispatcher,
+            running: Mutex::new(true),
+            ui_thread: thread::current().id(),
+            pending: Mutex::default(),
+        })
+    }
+
+    /// Called once the main loop has returned, the webview may be gone after that.
+    pub(crate) fn stop(&self) {
+        *self.running() = false;
+        let pending = std::mem::take(&mut *self.pending());
+        for slot in pending.iter().filter_map(Weak::upgrade) {
+            fill(&slot, Err(closed()));
+        }
+    }
+
+    fn pending(&self) -> MutexGuard<'_, Vec<Weak<Mutex<Slot>>>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn running(&self) -> MutexGuard<'_, bool> {
+        self.running.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Queues `f` on the UI thread, the caller holding `running()` and having seen it set.
+    /// Anything already queued when the window stops is skipped, the loop may outlive it.
+    fn enqueue<F>(self: &Arc<Self>, f: F)
+    where
+        F: FnOnce(&mut crate::webview::Webview) + Send + 'static,
+    {
+        let shared = Arc::clone(self);
+        self.dispatcher.dispatch(move |webview| {
+            if *shared.running() {
+                f(webview);
+            }
+        });
+    }
+}
+
+impl QuarkHandle {
+    pub(crate) fn new(shared: Arc<Shared>) -> Self {
+        QuarkHandle { shared }
+    }
+
+    pub(crate) fn dispatch<F>(&self, f: F)
+    where
+        F: FnOnce(&mut crate::webview::Webview) + Send + 'static,
+    {
+        if *self.shared.running() {
+            self.shared.enqueue(f);
+        }
+    }
+
+    /// Evaluates `js` in the window.
+    pub fn eval(&self, js: &str) {
+        let js = js.to_owned();
+        self.dispatch(move |webview| webview.eval(&js));
+    }
+
+    /// Evaluates `js` in the window, then calls `callback` on the UI thread with the value of
+    /// its last expression, see `Quark::eval_with_callback`. On macOS it gets
+    /// `QuarkError::Unsupported` for now.
+    pub fn eval_with_callback<F>(&self, js: &str, callback: F)
+    where
+        F: FnOnce(Result<Value, QuarkError>) + Send + 'static,
+    {
+        let running = self.shared.running();
+        if !*running {
+            drop(running);
+            return callback(Err(closed()));
+        }
+        let js = js.to_owned();
+        self.shared
+            .enqueue(move |webview| webview.eval_with_callback(&js, callback));
+    }
+
+    /// Like [`QuarkHandle::eval_with_callback`], but returns a future of the result, for
+    /// `Quark::command_async` handlers and other threads. Don't block the UI thread on it, the
+    /// script can't run meanwhile. It fails with `QuarkError::Unsupported` on macOS for now.
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// let handle = quark.handle();
+    /// quark.command_async("wordCount", move |()| {
+    ///     let text = handle.eval_async("document.body.innerText");
+    ///     async move {
+    ///         let text = text.await.map_err(|e| e.to_string())?;
+    ///         Ok::<_, String>(text.as_str().unwrap_or_default().split_whitespace().count())
+    ///     }
+    /// });
+    /// ```
+    pub fn eval_async(&self, js: &str) -> EvalFuture {
+        let slot = Arc::new(Mutex::new(Slot::default()));
+        let mut pending = self.shared.pending();
+        pending.retain(|slot| slot.strong_count() > 0);
+        pending.push(Arc::downgrade(&slot));
+        drop(pending);
+        let filler = Filler(Arc::clone(&slot));
+        self.eval_with_callback(js, move |result| filler.fill(result));
+        EvalFuture { slot }
+    }
+
+    /// Like [`QuarkHandle::eval_async`], but blocks until the result is there. Fails with
+    /// `QuarkError::JavaScriptError` on the UI thread, which would wait on itself, and with
+    /// `QuarkError::Unsupported` on macOS.
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// let handle = quark.handle();
+    /// std::thread::spawn(move || {
+    ///     let title = handle.eval_with_result("document.title");
+    /// });
+    /// ```
+    pub fn eval_with_result(&self, js: &str) -> Result<Value, QuarkError> {
+        if thread::current().id() == self.shared.ui_thread {
+            return Err(QuarkError::JavaScriptError(String::from(
+                "eval_with_result can't block the UI thread, use eval_with_callback",
+            )));
+        }
+        crate::command::block_on(self.eval_async(js))
+    }
+
+    /// Sets the title of the window.
+    pub fn set_title(&self, title: &str) {
+        let title = title.to_owned();
+        self.dispatch(move |webview| webview.set_title(&title));
+    }
+
+    /// Sets the size of the window, see [`SizeHint`].
+    pub fn set_size(&self, width: u16, height: u16, hint: SizeHint) {
+        self.dispatch(move |webview| webview.set_size(width, height, hint));
+    }
+
+    /// Maximizes the window, or restores it with `false`.
+    pub fn set_maximized(&self, maximized: bool) {
+        self.dispatch(move |webview| webview.set_maximized(maximized));
+    }
+
+    /// Minimizes the window, or restores it with `false`.
+    pub fn set_minimized(&self, minimized: bool) {
+        self.dispatch(move |webview| webview.set_minimized(minimized));
+    }
+
+    /// Makes the window fullscreen, or leaves fullscreen with `false`.
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        self.dispatch(move |webview| webview.set_fullscreen(fullscreen));
+    }
+
+    /// Switches the window in or out of fullscreen.
+    pub fn toggle_fullscreen(&self) {
+        self.dispatch(|webview| {
+            let fullscreen = webview.state().fullscreen;
+            webview.set_fullscreen(!fullscreen);
+        });
+    }
+
+    /// Moves the window's top left corner to `x`, `y`, ignored on Wayland.
+    pub fn set_position(&self, x: i32, y: i32) {
+        self.dispatch(move |webview| webview.set_position(x, y));
+    }
+
+    /// Moves the window to the middle of the screen it's on.
+    pub fn center(&self) {
+        self.dispatch(|webview| webview.center());
+    }
+
+    /// Keeps the window above the others, or stops doing so with `false`.
+    pub fn set_always_on_top(&self, on_top: bool) {
+        self.dispatch(move |webview| webview.set_always_on_top(on_top));
+    }
+
+    /// Shows the window, or hides it with `false`.
+    pub fn set_visible(&self, visible: bool) {
+        self.dispatch(move |webview| webview.set_visible(visible));
+    }
+
+    /// Raises the window and gives it the keyboard focus.
+    pub fn focus(&self) {
+        self.dispatch(|webview| webview.focus());
+    }
+
+    /// Shows or hides the window's title bar and borders.
+    pub fn set_decorations(&self, decorations: bool) {
+        self.dispatch(move |webview| webview.set_decorations(decorations));
+    }
+
+    /// Navigates the window to `url`, failing with `QuarkError::InvalidUrl` if it's malformed.
+    pub fn navigate(&self, url: &str) -> Result<(), QuarkError> {
+        url::validate(url)?;
+        let url = url.to_owned();
+        self.dispatch(move |webview| {
+            let _ = webview.navigate(&url); // already validated
+        });
+        Ok(())
+    }
+
+    /// Sends `event` to the page's `quark.on` listeners, with `payload` serialized as JSON.
+    pub fn emit<T: Serialize>(&self, event: &str, payload: T) {
+        if let Ok(js) = event::emit_js(event, &payload) {
+            self.eval(&js);
+        }
+    }
+
+    /// Asks the window to close, the same as its close button does.
+    pub fn close(&self) {
+        self.dispatch(|webview| webview.close(false));
+    }
+
+    /// Shows `dialog` over the window, then calls `then` on the UI thread with the picked
+    /// paths, none if the user cancelled. Fails with `QuarkError::Unsupported` on macOS, which
+    /// has no file dialog yet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// let dialog = FileDialog::new().title("Open project");
+    /// handle.file_dialog(dialog, FileDialogMode::PickFolder, |paths| {
+    ///     if let Some(project) = paths.first() {
+    ///         println!("Opening {}", project.display());
+    ///     }
+    /// })?;
+    /// ```
+    pub fn file_dialog<F>(
+        &self,
+        dialog: FileDialog,
+        mode: FileDialogMode,
+        then: F,
+    ) -> Result<(), QuarkError>
+    where
+        F: FnOnce(Vec<PathBuf>) + Send + 'static,
+    {
+        // Checked here, the UI thread has no one to tell
+        crate::webview::supported(crate::dialog::FILE_DIALOGS)?;
+        self.dispatch(move |webview| {
+            let _ = dialog.show(webview, mode, then);
+        });
+        Ok(())
+    }
+
+    /// Shows `dialog` over the window, then calls `then` on the UI thread with the user's
+    /// answer. Fails with `QuarkError::Unsupported` on macOS, which has no message dialog yet.
+    pub fn message_dialog<F>(&self, dialog: MessageDialog, then: F) -> Result<(), QuarkError>
+    where
+        F: FnOnce(MessageResponse) + Send + 'static,
+    {
+        crate::webview::supported(crate::dialog::MESSAGE_DIALOGS)?;
+        self.dispatch(move |webview| {
+            let _ = dialog.run(webview, then);
+        });
+        Ok(())
+    }
+
+    /// Ends the main loop, making `Quark::run` return.
+    pub fn exit(&self) {
+        self.dispatch(|webview| webview.terminate());
+    }
+}
+
+fn closed() -> QuarkError {
+    QuarkError::JavaScriptError(String::from("The window has closed"))
+}
+
+/// The result of [`QuarkHandle::eval_async`], failing with `QuarkError::JavaScriptError` if
+/// the window closes or the main loop ends before the script runs.
+#[must_use = "futures do nothing unless they're awaited"]
+pub struct EvalFuture {
+    slot: Arc<Mutex<Slot>>,
+}
+
+#[derive(Default)]
+struct Slot {
+    result: Option<Result<Value, QuarkError>>,
+    waker: Option<Waker>,
+}
+
+/// Fills the slot of an [`EvalFuture`], with an error if it's dropped before the result came
+/// back, as happens when the window closes in the meantime.
+struct Filler(Arc<Mutex<Slot>>);
+
+impl Filler {
+    fn fill(&self, result: Result<Value, QuarkError>) {
+        fill(&self.0, result);
+    }
+}
+
+/// Fills `slot` unless it's filled already, waking whoever waits on it.
+fn fill(slot: &Mutex<Slot>, result: Result<Value, QuarkError>) {
+    let Ok(mut slot) = slot.lock() else {
+        return;
+    };
+    slot.result.get_or_insert(result);
+    if let Some(waker) = slot.waker.take() {
+        waker.wake();
+    }
+}
+
+impl Drop for Filler {
+    fn drop(&mut self) {
+        self.fill(Err(closed()));
+    }
+}
+
+impl Future for EvalFuture {
+    type Output = Result<Value, QuarkError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Ok(mut slot) = self.slot.lock() else {
+            return Poll::Ready(Err(closed()));
+        };
+        match slot.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -1,43 +1,187 @@
+pub mod app;
 pub mod cli;
+pub mod command;
 pub mod config;
+pub mod console;
+pub mod dialog;
 pub mod error;
+pub mod event;
+pub mod frontend;
+pub mod handle;
+pub mod manifest;
+pub mod mime;
+pub mod navigation;
 pub mod prelude;
+pub mod url;
 pub mod webview;
+pub mod window;
 
+use crate::cli::build_dev_url::*;
 use crate::cli::build_http::*;
 use crate::cli::build_static::*;
-use crate::webview::{Webview, WebviewBuilder};
+use crate::webview::{Webview, WebviewBuilder, WindowEvent, WindowGeometry, WindowState};
 use config::QuarkConfig;
 use error::QuarkError;
+use handle::QuarkHandle;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+#[doc(hidden)]
+pub use include_dir; // used by the `frontend!` macro
 
 #[allow(dead_code)]
 pub struct Quark {
     webview: Webview,
     config: QuarkConfig,
+    shared: Arc<handle::Shared>,
+    listeners: event::Listeners,
+    window_listeners: window::WindowListeners,
+    args: Vec<String>,
+    label: String,
 }
 
 impl Quark {
+    /// Creates the window, leaving the whole command line to the application. See
+    /// [`Quark::args`] to read it, dev mode is started with `cargo quark dev` instead.
     pub fn new(config: QuarkConfig) -> Result<Self, QuarkError> {
-        let args = cli::parse_args();
+        Self::with_args(config, cli::Args::from_env())
+    }
+
+    /// Like [`Quark::new`], but applies the dev flags found in `args`, usually coming from
+    /// [`cli::parse_args`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// let quark = Quark::with_args(config, libquark::cli::parse_args())?;
+    /// ```
+    pub fn with_args(config: QuarkConfig, args: cli::Args) -> Result<Self, QuarkError> {
+        Self::build(config, args, app::MAIN_LABEL)
+    }
+
+    pub(crate) fn build(
+        mut config: QuarkConfig,
+        args: cli::Args,
+        label: &str,
+    ) -> Result<Self, QuarkError> {
+        let args = args.with_dev_env();
+        if let Some(host) = args.host {
+            config.dev_host = host;
+        }
+        if let Some(port) = args.port {
+            config.dev_port = port;
+        }
+        if let Some(url) = args.dev_url {
+            if cfg!(debug_assertions) {
+                config.dev_url = Some(url);
+            } else {
+                log::warn!("Ignoring '--dev-url', release builds use the embedded frontend.");
+            }
+        }
+        if !cfg!(debug_assertions) {
+            config.dev_url = None;
+        }
+
+        if config.frontend.is_none() {
+            return Err(QuarkError::FrontendPathMissing);
+        }
+
+        config.validate_size()?;
+        config.validate_platform()?;
+        // Can't fail once the sizes are validated
+        let to_u16 = |length: usize| {
+            u16::try_from(length).map_err(|e| QuarkError::InvalidWindowSize(e.to_string()))
+        };
 
-        let webview = WebviewBuilder::new()
+        let mut builder = WebviewBuilder::new()
             .title(&config.title)
             .width(config.width)
             .height(config.height)
             .resize(config.resizable)
-            .debug(cfg!(debug_assertions))
-            .build();
+            .debug(config.devtools);
+        if let Some((width, height)) = config.min_size {
+            builder = builder.min_size(to_u16(width)?, to_u16(height)?);
+        }
+        if let Some((width, height)) = config.max_size {
+            builder = builder.max_size(to_u16(width)?, to_u16(height)?);
+        }
+        if config.lock_aspect_ratio {
+            builder = builder.aspect_ratio(config.width as f64 / config.height as f64);
+        }
+        if let Some((x, y)) = config.position {
+            builder = builder.position(x, y);
+        }
+        let webview = builder.build()?;
 
-        let mut quark = Quark { webview, config };
+        let shared = handle::Shared::new(webview.dispatcher());
+        let mut quark = Quark {
+            webview,
+            config,
+            shared,
+            listeners: event::Listeners::default(),
+            window_listeners: window::WindowListeners::default(),
+            args: args.rest,
+            label: label.to_owned(),
+        };
+        // First, so the console of the other scripts is caught too
+        console::install(
+            &mut quark.webview,
+            quark.config.console_handler.clone(),
+            quark.config.devtools,
+        );
+        event::install(&mut quark.webview, quark.listeners.clone());
+        window::install(&mut quark.webview, quark.window_listeners.clone());
+        // Before any other handler and before the widgets go, so the handle leaves them alone.
+        // The webview and everything it was handed go once the handlers are done.
+        let shared = Arc::clone(&quark.shared);
+        let webview = quark.webview.clone();
+        quark
+            .window_listeners
+            .on_window_event(Box::new(move |event| {
+                if *event == WindowEvent::Closed {
+                    shared.stop();
+                    webview.destroy_later();
+                }
+            }));
+        dialog::install(&mut quark.webview);
+        if quark.config.native_script_dialogs {
+            quark.webview.set_native_script_dialogs(true);
+        }
+        event::expose_args(&mut quark.webview, &quark.args);
 
-        if args.live {
+        if let Some(url) = quark.config.dev_url.clone() {
+            build_dev_url(&mut quark, &url)?;
+        } else if args.live {
             build_http(&mut quark)?;
         } else {
             build_static(&mut quark)?;
         }
+
+        let app_url = quark.webview.url().to_owned();
+        navigation::install(
+            &mut quark.webview,
+            &app_url,
+            quark.config.allowed_origins.clone(),
+            quark.config.navigation_handler.clone(),
+        );
         Ok(quark)
     }
 
+    /// Returns the command line arguments meant for the application, without the program name
+    /// (and without Quark's dev flags when created with [`Quark::with_args`]). Pages can read
+    /// them from `window.quark.args`.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Returns the window's label, `main` unless it was opened with [`app::QuarkApp::open`].
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
     pub fn bind<F>(&mut self, name: &str, handler: F)
     where
         F: FnMut(&str, &str) + 'static,
@@ -45,11 +189,255 @@ impl Quark {
         self.webview.bind(name, handler);
     }
 
+    /// Exposes `handler` to JavaScript as a global async function called `name`.
+    ///
+    /// The arguments of the JavaScript call are decoded from their JSON array into `A`, usually
+    /// a tuple such as `(String, u32)` (or `()` for none). Returning `Ok` resolves the promise
+    /// with the serialized value, returning `Err` rejects it with the serialized error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// quark.command("add", |(a, b): (i32, i32)| -> Result<i32, String> { Ok(a + b) });
+    /// ```
+    ///
+    /// ```js
+    /// const sum = await add(1, 2);
+    /// ```
+    pub fn command<A, R, E, F>(&mut self, name: &str, mut handler: F)
+    where
+        A: DeserializeOwned,
+        R: Serialize,
+        E: Serialize,
+        F: FnMut(A) -> Result<R, E> + 'static,
+    {
+        let webview = self.webview.clone();
+        let command = name.to_owned();
+        self.webview.bind(name, move |seq, req| {
+            let (status, result) = command::call(&command, &mut handler, req);
+            webview.r#return(seq, status, &result);
+        });
+    }
+
+    /// Like [`Quark::command`], but for long-running work that shouldn't freeze the window.
+    ///
+    /// `handler` is called on the UI thread with the decoded arguments and returns a future,
+    /// which is then driven on a worker thread. Once it completes, the JavaScript promise gets
+    /// settled back on the UI thread, it's rejected if the future panics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// quark.command_async("read", |(path,): (String,)| async move {
+    ///     std::fs::read_to_string(path).map_err(|e| e.to_string())
+    /// });
+    /// ```
+    pub fn command_async<A, R, E, F, Fut>(&mut self, name: &str, mut handler: F)
+    where
+        A: DeserializeOwned,
+        R: Serialize,
+        E: Serialize,
+        F: FnMut(A) -> Fut + 'static,
+        Fut: Future<Output = Result<R, E>> + Send + 'static,
+    {
+        let webview = self.webview.clone();
+        let handle = self.handle();
+        let command = name.to_owned();
+        self.webview.bind(name, move |seq, req| {
+            let future = match command::decode_args::<A>(req) {
+                Ok(args) => handler(args),
+                Err(e) => {
+                    let (status, result) = command::invalid_args(&command, &e);
+                    webview.r#return(seq, status, &result);
+                    return;
+                }
+            };
+            let seq = seq.to_owned();
+            let name = command.clone();
+            let handle = handle.clone();
+            command::spawn(move || {
+                let settled = panic::catch_unwind(AssertUnwindSafe(|| command::block_on(future)));
+                let (status, result) = match settled {
+                    Ok(result) => command::settle(result),
+                    Err(_) => command::panicked(&name),
+                };
+                // Dropped if the window has closed meanwhile
+                handle.dispatch(move |webview| webview.r#return(&seq, status, &result));
+            });
+        });
+    }
+
+    /// Sends `event` to the page's `quark.on` listeners, with `payload` serialized as JSON.
+    pub fn emit<T: Serialize>(&mut self, event: &str, payload: T) {
+        if let Ok(js) = event::emit_js(event, &payload) {
+            self.webview.eval(&js);
+        }
+    }
+
+    /// Calls `handler` every time the page emits `event` with `quark.emit`.
+    ///
+    /// The payload is decoded into `T`, use `serde_json::Value` to take anything. Payloads that
+    /// don't fit `T` are skipped, with a warning through the `log` crate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// quark.listen("save", |path: String| println!("saving {path}"));
+    /// ```
+    ///
+    /// ```js
+    /// quark.emit("save", "notes.txt");
+    /// ```
+    pub fn listen<T, F>(&mut self, event: &str, mut handler: F)
+    where
+        T: DeserializeOwned,
+        F: FnMut(T) + 'static,
+    {
+        let name = event.to_owned();
+        self.listeners.add(
+            event,
+            Box::new(move |payload| match T::deserialize(payload) {
+                Ok(payload) => handler(payload),
+                Err(e) => log::warn!("Ignoring the '{name}' event, its payload doesn't fit: {e}"),
+            }),
+        );
+    }
+
+    /// Navigates the window to `url`, failing with `QuarkError::InvalidUrl` if it's malformed.
+    pub fn navigate(&mut self, url: &str) -> Result<(), QuarkError> {
+        self.webview.navigate(url)
+    }
+
     pub fn eval(&mut self, js: &str) {
         self.webview.eval(js);
     }
 
+    /// Evaluates `js` and calls `callback` with the value of its last expression, or
+    /// `QuarkError::JavaScriptError` with the message of the exception it threw. Returns right
+    /// away, the result comes in a later iteration of the main loop. `undefined` and functions
+    /// come back as `null`, and promises aren't waited for.
+    ///
+    /// macOS can't hand results back yet, `callback` gets `QuarkError::Unsupported` there.
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// quark.eval_with_callback("document.title", |title| match title {
+    ///     Ok(title) => println!("Title: {title}"),
+    ///     Err(e) => eprintln!("{e}"),
+    /// });
+    /// ```
+    pub fn eval_with_callback<F>(&mut self, js: &str, callback: F)
+    where
+        F: FnOnce(Result<serde_json::Value, QuarkError>) + 'static,
+    {
+        self.webview.eval_with_callback(js, callback);
+    }
+
+    /// Calls `handler` when the user asks to close the window, which stays open if it returns
+    /// `false`. With several handlers, the first to return `false` keeps it open.
+    ///
+    /// Fails with `QuarkError::Unsupported` on macOS, which doesn't send window events yet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust, ignore
+    /// quark.on_close_requested(move || !unsaved.get())?;
+    /// ```
+    pub fn on_close_requested<F>(&mut self, handler: F) -> Result<(), QuarkError>
+    where
+        F: FnMut() -> bool + 'static,
+    {
+        webview::supported(window::WINDOW_EVENTS)?;
+        self.window_listeners.on_close_requested(Box::new(handler));
+        Ok(())
+    }
+
+    /// Calls `handler` with every [`WindowEvent`] of the window: close requests, resizes,
+    /// moves, focus changes, and it getting maximized, minimized or fullscreen. Use
+    /// [`Quark::on_close_requested`] to keep it from closing. Fails with
+    /// `QuarkError::Unsupported` on macOS, like it.
+    pub fn on_window_event<F>(&mut self, handler: F) -> Result<(), QuarkError>
+    where
+        F: FnMut(&WindowEvent) + 'static,
+    {
+        webview::supported(window::WINDOW_EVENTS)?;
+        self.window_listeners.on_window_event(Box::new(handler));
+        Ok(())
+    }
+
+    /// Asks the window to close, the same as its close button does.
+    pub fn close(&mut self) {
+        self.webview.close(false);
+    }
+
+    /// Maximizes the window, or restores it with `false`.
+    pub fn set_maximized(&mut self, maximized: bool) {
+        self.webview.set_maximized(maximized);
+    }
+
+    /// Minimizes the window, or restores it with `false`.
+    pub fn set_minimized(&mut self, minimized: bool) {
+        self.webview.set_minimized(minimized);
+    }
+
+    /// Makes the window fullscreen, or leaves fullscreen with `false`. To toggle it, pass
+    /// `!quark.state().fullscreen`.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.webview.set_fullscreen(fullscreen);
+    }
+
+    /// Moves the window's top left corner to `x`, `y`. Ignored on Wayland, where the compositor
+    /// places windows itself.
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.webview.set_position(x, y);
+    }
+
+    /// Moves the window to the middle of the screen it's on.
+    pub fn center(&mut self) {
+        self.webview.center();
+    }
+
+    /// Keeps the window above the others, or stops doing so with `false`.
+    pub fn set_always_on_top(&mut self, on_top: bool) {
+        self.webview.set_always_on_top(on_top);
+    }
+
+    /// Shows the window, or hides it with `false`. The application keeps running while it's
+    /// hidden.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.webview.set_visible(visible);
+    }
+
+    /// Raises the window and gives it the keyboard focus.
+    pub fn focus(&mut self) {
+        self.webview.focus();
+    }
+
+    /// Shows or hides the window's title bar and borders.
+    pub fn set_decorations(&mut self, decorations: bool) {
+        self.webview.set_decorations(decorations);
+    }
+
+    /// Returns where the window is and how big it is.
+    pub fn geometry(&self) -> WindowGeometry {
+        self.webview.geometry()
+    }
+
+    /// Returns whether the window is maximized, minimized, fullscreen, visible and focused.
+    pub fn state(&self) -> WindowState {
+        self.webview.state()
+    }
+
+    /// Returns a [`QuarkHandle`] for driving this window from other threads, even while it runs.
+    pub fn handle(&self) -> QuarkHandle {
+        QuarkHandle::new(Arc::clone(&self.shared))
+    }
+
     pub fn run(mut self) {
         self.webview.run();
+        self.shared.stop();
+        // The loop may have ended before the window was destroyed
+        self.webview.destroy();
     } // mmm x3
 }
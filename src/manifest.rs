@@ -0,0 +1,318 @@
+//! The `Quark.toml` application manifest, one file describing the window, the frontend, the dev
+//! server and how the application gets bundled. Every key is optional.
+//!
+//! ```toml
+//! [window]
+//! title = "My App"
+//! width = 800
+//! height = 600
+//! resizable = "none" # "none", "min", "max" or "fixed"
+//! min_size = [400, 300]
+//! max_size = [1600, 1200]
+//! lock_aspect_ratio = false
+//! position = [100, 100] # left to the window manager when left out
+//! route = "/index.html"
+//! native_script_dialogs = false # alert(), confirm() and prompt() as native dialogs
+//!
+//! [frontend]
+//! dir = "src_quark" # next to Quark.toml
+//!
+//! [dev]
+//! host = "127.0.0.1"
+//! port = 24114
+//! url = "http://localhost:5173"
+//!
+//! [security]
+//! devtools = false # the web inspector, on in debug builds by default
+//! allowed_origins = ["https://*.example.com"] # where the window may navigate besides the app
+//!
+//! [bundle]
+//! name = "My App"
+//! identifier = "com.example.my-app"
+//! icon = ["icons/icon*.png"]
+//! ```
+//!
+//! The application reads it with [`manifest!`] when it's compiled, `cargo quark bundle` reads
+//! its `[bundle]` table instead of `[package.metadata.bundle]`.
+//!
+//! [`manifest!`]: crate::manifest!
+
+use crate::config::QuarkConfig;
+use crate::error::QuarkError;
+use crate::frontend::Frontend;
+use crate::webview::SizeHint;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// What the manifest is called, it sits next to `Cargo.toml`.
+pub const FILE_NAME: &str = "Quark.toml";
+
+const DEFAULT_FRONTEND_DIR: &str = "src_quark";
+
+/// Whether `identifier` is a reverse DNS bundle identifier like `com.example.app`: at least two
+/// dot separated parts made of ASCII letters, digits and `-`.
+pub fn is_valid_identifier(identifier: &str) -> bool {
+    identifier.contains('.')
+        && identifier.split('.').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Manifest {
+    pub window: WindowManifest,
+    pub frontend: FrontendManifest,
+    pub dev: DevManifest,
+    pub security: SecurityManifest,
+    pub bundle: BundleManifest,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowManifest {
+    pub title: Option<String>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub resizable: Option<SizeHint>,
+    pub min_size: Option<(usize, usize)>,
+    pub max_size: Option<(usize, usize)>,
+    pub lock_aspect_ratio: Option<bool>,
+    pub position: Option<(i32, i32)>,
+    pub native_script_dialogs: Option<bool>,
+    pub route: Option<String>,
+}
+
+impl WindowManifest {
+    /// Returns `config` with everything set here applied to it.
+    pub fn apply(&self, mut config: QuarkConfig) -> QuarkConfig {
+        if let Some(title) = &self.title {
+            config = config.title(title);
+        }
+        if let Some(width) = self.width {
+            config = config.width(width);
+        }
+        if let Some(height) = self.height {
+            config = config.height(height);
+        }
+        if let Some(resizable) = self.resizable {
+            config = config.resizable(resizable);
+        }
+        if let Some((width, height)) = self.min_size {
+            config = config.min_size(width, height);
+        }
+        if let Some((width, height)) = self.max_size {
+            config = config.max_size(width, height);
+        }
+        if let Some(lock) = self.lock_aspect_ratio {
+            config = config.lock_aspect_ratio(lock);
+        }
+        if let Some((x, y)) = self.position {
+            config = config.position(x, y);
+        }
+        if let Some(native) = self.native_script_dialogs {
+            config = config.native_script_dialogs(native);
+        }
+        if let Some(route) = &self.route {
+            config = config.route(route);
+        }
+        config
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrontendManifest {
+    /// Relative to the manifest, `src_quark` when left out.
+    pub dir: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DevManifest {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SecurityManifest {
+    pub devtools: Option<bool>,
+    pub allowed_origins: Option<Vec<String>>,
+}
+
+/// Same keys as `[package.metadata.bundle]`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BundleManifest {
+    pub name: Option<String>,
+    pub identifier: Option<String>,
+    pub icon: Option<Vec<String>>,
+    pub version: Option<String>,
+    pub resources: Option<Vec<String>>,
+    pub copyright: Option<String>,
+    pub category: Option<String>,
+    pub short_description: Option<String>,
+    pub long_description: Option<String>,
+    pub linux_mime_types: Option<Vec<String>>,
+    pub linux_exec_args: Option<String>,
+    pub linux_use_terminal: Option<bool>,
+    pub deb_depends: Option<Vec<String>>,
+    pub osx_frameworks: Option<Vec<String>>,
+    pub osx_minimum_system_version: Option<String>,
+    pub osx_url_schemes: Option<Vec<String>>,
+}
+
+impl Manifest {
+    /// Parses and validates the contents of a `Quark.toml`, failing with
+    /// `QuarkError::InvalidManifest` describing what's wrong and where.
+    pub fn parse(source: &str) -> Result<Self, QuarkError> {
+        let manifest: Manifest = toml::from_str(source)
+            .map_err(|e| QuarkError::InvalidManifest(format!("{FILE_NAME}: {e}")))?;
+        manifest
+            .validate()
+            .map_err(|e| QuarkError::InvalidManifest(format!("{FILE_NAME}: {e}")))?;
+        Ok(manifest)
+    }
+
+    /// Reads and parses the manifest at `path`.
+    pub fn load(path: &Path) -> Result<Self, QuarkError> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            QuarkError::InvalidManifest(format!("Couldn't read {}: {e}", path.display()))
+        })?;
+        Self::parse(&source)
+    }
+
+    /// Returns the frontend folder, relative to the manifest.
+    pub fn frontend_dir(&self) -> &str {
+        self.frontend.dir.as_deref().unwrap_or(DEFAULT_FRONTEND_DIR)
+    }
+
+    /// Returns a [`QuarkConfig`] with everything the manifest sets, the frontend excepted.
+    pub fn config(&self) -> QuarkConfig {
+        let mut config = self.window.apply(QuarkConfig::new());
+        if let Some(host) = &self.dev.host {
+            config = config.dev_host(host);
+        }
+        if let Some(port) = self.dev.port {
+            config = config.dev_port(port);
+        }
+        if let Some(url) = &self.dev.url {
+            config = config.dev_url(url);
+        }
+        if let Some(devtools) = self.security.devtools {
+            config = config.devtools(devtools);
+        }
+        if let Some(origins) = &self.security.allowed_origins {
+            let origins: Vec<&str> = origins.iter().map(String::as_str).collect();
+            config = config.allowed_origins(&origins);
+        }
+        config
+    }
+
+    /// Used by [`manifest!`], `source` being the embedded manifest, `frontend` the folder
+    /// embedded along with it and `manifest_dir` the calling crate's `CARGO_MANIFEST_DIR`.
+    ///
+    /// [`manifest!`]: crate::manifest!
+    #[doc(hidden)]
+    pub fn __embed(
+        source: &str,
+        frontend: Frontend,
+        manifest_dir: &str,
+    ) -> Result<QuarkConfig, QuarkError> {
+        let manifest = Self::parse(source)?;
+
+        // The folder has to be given to `include_dir!` as a literal, so the macro can't read it
+        // from the manifest itself
+        let expected = Path::new(manifest_dir).join(manifest.frontend_dir());
+        if let Some(source) = frontend.source() {
+            if source.components().ne(expected.components()) {
+                return Err(QuarkError::InvalidManifest(format!(
+                    "{FILE_NAME}: the frontend is in '{dir}', but manifest!() embedded {source:?}. \
+                     Use manifest!(\"$CARGO_MANIFEST_DIR/{dir}\") instead.",
+                    dir = manifest.frontend_dir(),
+                )));
+            }
+        }
+        Ok(manifest.config().frontend(frontend))
+    }
+
+    /// Catches what the schema alone can't.
+    fn validate(&self) -> Result<(), String> {
+        for (key, size) in [("width", self.window.width), ("height", self.window.height)] {
+            if size.is_some_and(|size| size == 0 || size > u16::MAX as usize) {
+                return Err(format!(
+                    "`window.{key}` has to be between 1 and {}",
+                    u16::MAX
+                ));
+            }
+        }
+
+        if let Err(QuarkError::InvalidWindowSize(e)) = self.config().validate_size() {
+            return Err(format!("`window`: {e}"));
+        }
+
+        let dir = Path::new(self.frontend_dir());
+        if dir.is_absolute() || dir.components().any(|c| c.as_os_str() == "..") {
+            return Err(format!(
+                "`frontend.dir` has to be a folder next to {FILE_NAME}, not {dir:?}"
+            ));
+        }
+
+        if self
+            .dev
+            .host
+            .as_ref()
+            .is_some_and(|host| host.trim().is_empty())
+        {
+            return Err("`dev.host` can't be empty".to_owned());
+        }
+        if let Some(url) = &self.dev.url {
+            let http = ["http://", "https://"].iter().any(|scheme| {
+                url.get(..scheme.len())
+                    .is_some_and(|start| start.eq_ignore_ascii_case(scheme))
+            });
+            if !http || crate::url::validate(url).is_err() {
+                return Err(format!("`dev.url` has to be an http(s) URL, not {url:?}"));
+            }
+        }
+
+        if let Some(identifier) = &self.bundle.identifier {
+            if !is_valid_identifier(identifier) {
+                return Err(format!(
+                    "`bundle.identifier` should look like \"com.example.app\", not {identifier:?}"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the calling crate's `Quark.toml` at compile time and returns the [`QuarkConfig`] it
+/// describes, frontend included.
+///
+/// With no arguments the `src_quark` folder is embedded. A manifest pointing somewhere else
+/// through `frontend.dir` needs the same folder given here, as with [`frontend!`].
+///
+/// # Examples
+///
+/// ```rust, ignore
+/// let quark = Quark::new(manifest!()?)?;
+/// ```
+///
+/// [`QuarkConfig`]: crate::config::QuarkConfig
+/// [`frontend!`]: crate::frontend!
+#[macro_export]
+macro_rules! manifest {
+    () => {
+        $crate::manifest!("$CARGO_MANIFEST_DIR/src_quark")
+    };
+    ($frontend:tt) => {
+        $crate::manifest::Manifest::__embed(
+            include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/Quark.toml")),
+            $crate::frontend!($frontend),
+            env!("CARGO_MANIFEST_DIR"),
+        )
+    };
+}
@@ -0,0 +1,61 @@
+//! Extension based MIME type detection for the files of a Quark frontend.
+
+use std::path::Path;
+
+/// The MIME type served when the extension of a file is unknown.
+pub const DEFAULT: &str = "application/octet-stream";
+
+/// Returns the MIME type of a file, guessed from its extension.
+pub fn from_path<P: AsRef<Path>>(path: P) -> &'static str {
+    let extension = path
+        .as_ref()
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some(ext) => from_extension(ext),
+        None => DEFAULT,
+    }
+}
+
+/// Returns the MIME type for a lowercase file extension, without the leading dot.
+pub fn from_extension(extension: &str) -> &'static str {
+    match extension {
+        // Documents
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" | "cjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "xml" => "application/xml",
+        "txt" => "text/plain; charset=utf-8",
+        "md" => "text/markdown; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "pdf" => "application/pdf",
+        "wasm" => "application/wasm",
+        "webmanifest" => "application/manifest+json",
+        // Images
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "bmp" => "image/bmp",
+        // Fonts
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        // Audio and video
+        "mp3" => "audio/mpeg",
+        "ogg" | "oga" => "audio/ogg",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "ogv" => "video/ogg",
+        _ => DEFAULT,
+    }
+}
@@ -0,0 +1,147 @@
+//! Where the window may go, see [`QuarkConfig::allowed_origins`] and
+//! [`QuarkConfig::on_navigation`].
+//!
+//! Navigations are checked in this order:
+//!
+//! 1. The app's own origin, the current page's origin, `about:blank` and the allowed origins
+//!    are always fine.
+//! 2. Otherwise the `on_navigation` handler decides, if there's one.
+//! 3. Otherwise links the user clicked, `target="_blank"` and `window.open()` included, open in
+//!    the user's browser when they're `http`, `https`, `mailto` or `tel` links.
+//! 4. Anything else is denied.
+//!
+//! macOS doesn't check navigations yet, so the window goes anywhere there. Windows with
+//! allowed origins or a handler fail to open with `QuarkError::Unsupported` rather than
+//! quietly letting everything through, others get a warning.
+//!
+//! [`QuarkConfig::allowed_origins`]: crate::config::QuarkConfig::allowed_origins
+//! [`QuarkConfig::on_navigation`]: crate::config::QuarkConfig::on_navigation
+
+use crate::url;
+use crate::webview::{NavigationKind, Webview};
+use std::io;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+/// Named by `QuarkError::Unsupported` on macOS.
+pub(crate) const POLICIES: &str = "navigation policies";
+
+/// Schemes handed to the user's browser or mail client by default.
+const EXTERNAL_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+/// A navigation the page is about to make.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationRequest {
+    /// Where it leads
+    pub url: String,
+    /// The page it starts from
+    pub current_url: String,
+    pub kind: NavigationKind,
+    /// Whether the user started it, by clicking a link for instance
+    pub user_gesture: bool,
+}
+
+/// What to do with a [`NavigationRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationDecision {
+    /// Go there, in this window even when a new one was asked for
+    Allow,
+    /// Stay on the current page
+    Deny,
+    /// Stay on the current page and open the URL with the user's browser
+    OpenExternally,
+}
+
+pub(crate) type NavigationHandler = Rc<dyn Fn(&NavigationRequest) -> NavigationDecision>;
+
+/// Opens `url` with the application the desktop picks for it, `xdg-open` on Linux and `open` on
+/// macOS.
+pub fn open_externally(url: &str) -> io::Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    let mut child = Command::new(opener)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .spawn()?;
+    // Reaped on the side, `xdg-open` may wait for the browser it started
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Whether `origin` is in `allowed`, whose entries may start with `*.` to take in subdomains,
+/// e.g. `https://*.example.com`.
+pub(crate) fn is_allowed(origin: &str, allowed: &[String]) -> bool {
+    allowed.iter().any(|pattern| {
+        let pattern = pattern.trim_end_matches('/').to_ascii_lowercase();
+        match pattern.split_once("://*.") {
+            Some((scheme, domain)) => origin
+                .strip_prefix(scheme)
+                .and_then(|rest| rest.strip_prefix("://"))
+                .and_then(|host| host.strip_suffix(domain))
+                .is_some_and(|subdomain| subdomain.ends_with('.') && subdomain.len() > 1),
+            None => origin == pattern,
+        }
+    })
+}
+
+/// Checks every navigation of `webview` against the rules above, `app_url` being the page it
+/// was started with.
+pub(crate) fn install(
+    webview: &mut Webview,
+    app_url: &str,
+    allowed_origins: Vec<String>,
+    handler: Option<NavigationHandler>,
+) {
+    let app_origin = url::origin(app_url);
+    webview.on_navigation(move |target, current, kind, user_gesture| {
+        let origin = url::origin(target);
+        let trusted = match &origin {
+            Some(origin) => {
+                Some(origin) == app_origin.as_ref()
+                    || Some(origin) == url::origin(current).as_ref()
+                    || is_allowed(origin, &allowed_origins)
+            }
+            None => target.starts_with("about:"),
+        };
+        if trusted {
+            return true;
+        }
+
+        let decision = match &handler {
+            Some(handler) => handler(&NavigationRequest {
+                url: target.to_owned(),
+                current_url: current.to_owned(),
+                kind,
+                user_gesture,
+            }),
+            None => {
+                let scheme = target
+                    .split_once(':')
+                    .map(|(scheme, _)| scheme.to_ascii_lowercase())
+                    .unwrap_or_default();
+                if user_gesture
+                    && kind != NavigationKind::Other
+                    && EXTERNAL_SCHEMES.contains(&scheme.as_str())
+                {
+                    NavigationDecision::OpenExternally
+                } else {
+                    NavigationDecision::Deny
+                }
+            }
+        };
+        match decision {
+            NavigationDecision::Allow => true,
+            NavigationDecision::Deny => false,
+            NavigationDecision::OpenExternally => {
+                if let Err(e) = open_externally(target) {
+                    log::warn!("Couldn't open {target}: {e}");
+                }
+                false
+            }
+        }
+    });
+}
@@ -2,4 +2,17 @@
 //!
 //! This prelude contains the bare essentials to get a Quark project up and going. Use it with `use quark::prelude::*;` at the top of your `main.rs` file!
 
-pub use crate::{config::QuarkConfig, error::QuarkError, webview::SizeHint, Quark};
+pub use crate::{
+    app::QuarkApp,
+    config::QuarkConfig,
+    console::{ConsoleKind, ConsoleMessage},
+    dialog::{FileDialog, FileDialogMode, MessageDialog},
+    error::QuarkError,
+    frontend,
+    frontend::Frontend,
+    handle::QuarkHandle,
+    manifest,
+    navigation::{NavigationDecision, NavigationRequest},
+    webview::{MessageLevel, MessageResponse, SizeHint, WindowEvent, WindowGeometry, WindowState},
+    Quark,
+};
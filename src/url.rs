@@ -0,0 +1,93 @@
+//! Just enough URL parsing to tell a navigable URL from a malformed one, and where it leads.
+
+use crate::error::QuarkError;
+
+/// Schemes whose URLs are made of an authority (`//host:port`) followed by a path.
+const HIERARCHICAL: &[&str] = &["http", "https", "ws", "wss", "ftp", "file", "quark"];
+
+/// Checks that `url` is an absolute URL the webview can navigate to, e.g.
+/// `https://example.com/`, `quark://app/index.html`, `about:blank` or a `data:` URI.
+/// `javascript:` URLs aren't, run scripts with `Quark::eval` instead.
+pub fn validate(url: &str) -> Result<(), QuarkError> {
+    let invalid = || QuarkError::InvalidUrl(url.to_owned());
+
+    if url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(invalid());
+    }
+
+    let (scheme, rest) = url.split_once(':').ok_or_else(invalid)?;
+    let mut chars = scheme.chars();
+    let scheme_is_valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !scheme_is_valid || rest.is_empty() {
+        return Err(invalid());
+    }
+
+    let scheme = scheme.to_ascii_lowercase();
+    // It runs a script in the current page rather than going anywhere, that's `eval`'s job
+    if scheme == "javascript" {
+        return Err(invalid());
+    }
+    if !HIERARCHICAL.contains(&scheme.as_str()) {
+        return Ok(());
+    }
+
+    let authority = rest
+        .strip_prefix("//")
+        .ok_or_else(invalid)?
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    // `file:///path` is the only one that may leave the host out
+    if authority.is_empty() {
+        return if scheme == "file" {
+            Ok(())
+        } else {
+            Err(invalid())
+        };
+    }
+
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let (host, port) = match host.rsplit_once(':') {
+        // An IPv6 address keeps its colons inside the brackets
+        Some((host, port)) if !port.contains(']') => (host, Some(port)),
+        _ => (host, None),
+    };
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    if let Some(port) = port {
+        if !port.is_empty() && port.parse::<u16>().is_err() {
+            return Err(invalid());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the origin of `url`, `scheme://host[:port]` in lowercase, leaving out the user and
+/// the default port of `http` and `https`. URLs that don't have one, such as `about:blank` or
+/// `data:` URIs, give `None`, `blob:` URLs give the origin of the page that made them.
+pub fn origin(url: &str) -> Option<String> {
+    validate(url).ok()?;
+    let (scheme, rest) = url.split_once(':')?;
+    let scheme = scheme.to_ascii_lowercase();
+    if scheme == "blob" {
+        return origin(rest);
+    }
+    if !HIERARCHICAL.contains(&scheme.as_str()) || scheme == "file" {
+        return None;
+    }
+
+    let authority = rest.strip_prefix("//")?.split(['/', '?', '#']).next()?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host)
+        .to_ascii_lowercase();
+    let host = match (scheme.as_str(), host.rsplit_once(':')) {
+        ("http", Some((host, "80"))) | ("https", Some((host, "443"))) => host.to_owned(),
+        _ => host,
+    };
+    Some(format!("{scheme}://{host}"))
+}
@@ -1,13 +1,18 @@
-use std::ffi::{CStr, CString};
-// use std::mem;
+use crate::error::QuarkError;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::ffi::{CStr, CString, OsStr};
 use std::os::raw::*;
-use std::ptr::null_mut;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::ptr::{null, null_mut};
 use std::rc::Rc;
 
 pub enum Window {}
 
 #[repr(i32)]
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")] // `resizable = "fixed"` in Quark.toml
 pub enum SizeHint {
     /// Width and height are default size
     #[default]
@@ -20,22 +25,218 @@ pub enum SizeHint {
     FIXED = 3,
 }
 
-#[derive(Clone)]
+/// Where the window is and how big it is, in screen pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WindowGeometry {
+    /// Always 0 on Wayland, which keeps window positions to itself
+    pub x: i32,
+    /// Always 0 on Wayland, which keeps window positions to itself
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// What the window is currently doing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WindowState {
+    pub maximized: bool,
+    pub minimized: bool,
+    pub fullscreen: bool,
+    pub visible: bool,
+    pub focused: bool,
+}
+
+/// Something that happened to the window, see [`Webview::on_window_event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowEvent {
+    /// The user asked to close the window, which can be kept open
+    CloseRequested,
+    Resized {
+        width: i32,
+        height: i32,
+    },
+    /// Never sent on Wayland, which keeps window positions to itself
+    Moved {
+        x: i32,
+        y: i32,
+    },
+    /// `true` when the window gets the focus, `false` when it loses it
+    Focused(bool),
+    Maximized(bool),
+    Minimized(bool),
+    Fullscreen(bool),
+    /// The window is gone, it's the last event it sends
+    Closed,
+}
+
+/// What started a navigation, see [`Webview::on_navigation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationKind {
+    /// A link was clicked
+    Link,
+    /// A link with `target="_blank"` or `window.open()` asked for a new window
+    NewWindow,
+    /// Anything else: scripts, forms, redirects, reloads, frames...
+    Other,
+}
+
+/// What a file dialog picks, see [`Webview::file_dialog`].
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileDialogAction {
+    /// Existing files
+    Open = 0,
+    /// Where to save a file, the user confirms before overwriting one
+    Save = 1,
+    /// Existing folders
+    Folder = 2,
+}
+
+/// The kind of a message dialog, which picks its icon, see [`Webview::message_dialog`].
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")] // `level: "warning"` in JavaScript
+pub enum MessageLevel {
+    #[default]
+    Info = 0,
+    Warning = 1,
+    Error = 2,
+    Question = 3,
+}
+
+/// How the user answered a message dialog.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MessageResponse {
+    /// The index of the button clicked, `None` when the dialog was dismissed
+    pub button: Option<usize>,
+    /// Whether the checkbox was checked, if there was one
+    pub checked: bool,
+    /// The text typed in, if there was a text field and a button was clicked
+    pub input: Option<String>,
+}
+
+// The webview_event_t values of lib.h
+const EVENT_CLOSE_REQUESTED: c_int = 0;
+const EVENT_RESIZED: c_int = 1;
+const EVENT_MOVED: c_int = 2;
+const EVENT_FOCUSED: c_int = 3;
+const EVENT_STATE_CHANGED: c_int = 4;
+const EVENT_CLOSED: c_int = 5;
+
+// The webview_state_t flags of lib.h
+const STATE_MAXIMIZED: c_int = 1 << 0;
+const STATE_MINIMIZED: c_int = 1 << 1;
+const STATE_FULLSCREEN: c_int = 1 << 2;
+const STATE_VISIBLE: c_int = 1 << 3;
+const STATE_FOCUSED: c_int = 1 << 4;
+
+/// A pointer to a webview that may be sent to other threads.
+///
+/// It can only post closures onto the UI thread, `webview_dispatch` being the one call that's
+/// safe to make from anywhere, everything else happens inside the posted closure.
+#[derive(Clone, Copy)]
+pub struct Dispatcher(super::webview_t);
+
+unsafe impl Send for Dispatcher {}
+unsafe impl Sync for Dispatcher {}
+
+impl Dispatcher {
+    pub fn dispatch<F>(&self, f: F)
+    where
+        F: FnOnce(&mut Webview) + Send + 'static,
+    {
+        dispatch(self.0, f)
+    }
+}
+
+fn dispatch<F>(webview: super::webview_t, f: F)
+where
+    F: FnOnce(&mut Webview) + Send + 'static,
+{
+    let closure = Box::into_raw(Box::new(f));
+    extern "C" fn callback<F>(webview: super::webview_t, arg: *mut c_void)
+    where
+        F: FnOnce(&mut Webview) + Send + 'static,
+    {
+        // Borrowed from whoever owns the webview, it mustn't destroy it when it's done
+        let mut webview = Webview {
+            inner: Rc::new(Engine::new(webview)),
+            url: "".to_string(),
+            owned: false,
+        };
+        let closure: Box<F> = unsafe { Box::from_raw(arg as *mut F) };
+        (*closure)(&mut webview);
+    }
+    unsafe { super::webview_dispatch(webview, Some(callback::<F>), closure as *mut _) }
+}
+
+/// Fails with `QuarkError::Unsupported` on macOS, whose engine doesn't have `feature` yet.
+/// Checked before the engine is asked, its stubs would otherwise look like the user cancelling.
+pub(crate) fn supported(feature: &str) -> Result<(), QuarkError> {
+    if cfg!(target_os = "macos") {
+        return Err(QuarkError::Unsupported(feature.to_owned()));
+    }
+    Ok(())
+}
+
+/// What the clones of a webview share. Once its window is closed the engine is left alone,
+/// until it's destroyed along with the callbacks it was handed.
+struct Engine {
+    raw: Cell<super::webview_t>,
+    closed: Cell<bool>,
+    closures: RefCell<Vec<Box<dyn FnOnce()>>>,
+}
+
+impl Engine {
+    fn new(raw: super::webview_t) -> Self {
+        Engine {
+            raw: Cell::new(raw),
+            closed: Cell::new(false),
+            closures: RefCell::default(),
+        }
+    }
+
+    fn destroy(&self) {
+        let raw = self.raw.replace(null_mut());
+        if raw.is_null() {
+            return;
+        }
+        if !self.closed.replace(true) {
+            // Closed first, without ending the main loop other windows may share
+            unsafe {
+                super::webview_set_exit_on_close(raw, 0);
+                super::webview_close(raw, 1);
+            }
+        }
+        unsafe { super::webview_destroy(raw) }
+        // Only now, the engine may call them till it's gone
+        drop(self.closures.take());
+    }
+}
+
 pub struct Webview {
-    inner: Rc<super::webview_t>,
+    inner: Rc<Engine>,
     url: String,
+    owned: bool,
 }
 
-unsafe impl Send for Webview {}
-unsafe impl Sync for Webview {}
+// Clones are borrowed by bindings and handlers, only the one `create` returned destroys the
+// webview
+impl Clone for Webview {
+    fn clone(&self) -> Self {
+        Webview {
+            inner: Rc::clone(&self.inner),
+            url: self.url.clone(),
+            owned: false,
+        }
+    }
+}
 
 impl Drop for Webview {
     fn drop(&mut self) {
-        if Rc::strong_count(&self.inner) == 0 {
-            unsafe {
-                super::webview_terminate(*self.inner);
-                super::webview_destroy(*self.inner);
-            }
+        // A closed window's webview is destroyed from the main loop, see `destroy_later`
+        if self.owned && !self.inner.closed.get() {
+            self.inner.destroy();
         }
     }
 }
@@ -44,88 +245,576 @@ impl Webview {
     pub fn create(debug: bool, window: Option<&mut Window>) -> Webview {
         if let Some(w) = window {
             Webview {
-                inner: Rc::new(unsafe {
+                inner: Rc::new(Engine::new(unsafe {
                     super::webview_create(debug as c_int, w as *mut Window as *mut _)
-                }),
+                })),
                 url: "".to_string(),
+                owned: true,
             }
         } else {
             Webview {
-                inner: Rc::new(unsafe { super::webview_create(debug as c_int, null_mut()) }),
+                inner: Rc::new(Engine::new(unsafe {
+                    super::webview_create(debug as c_int, null_mut())
+                })),
                 url: "".to_string(),
+                owned: true,
             }
         }
     }
 
+    /// The engine, `None` once the window is closed, as nothing but destroying it is allowed
+    /// after that.
+    fn raw(&self) -> Option<super::webview_t> {
+        let raw = self.inner.raw.get();
+        (!self.inner.closed.get() && !raw.is_null()).then_some(raw)
+    }
+
+    /// Frees `closure` along with the webview, which calls it until then.
+    fn keep<F: 'static>(&self, closure: *mut F) {
+        let free = move || drop(unsafe { Box::from_raw(closure) });
+        self.inner.closures.borrow_mut().push(Box::new(free));
+    }
+
+    /// Closes the window if it's still open, then destroys the webview along with the
+    /// callbacks it was handed, every clone doing nothing afterwards. Not from the window's own
+    /// handlers, see [`Webview::destroy_later`].
+    pub fn destroy(&mut self) {
+        self.inner.destroy();
+    }
+
+    /// Destroys the webview from the main loop, once whatever handles its window's
+    /// `WindowEvent::Closed` is done with it. Every clone does nothing from now on.
+    pub fn destroy_later(&self) {
+        let raw = self.inner.raw.get();
+        if self.inner.closed.replace(true) || raw.is_null() {
+            return;
+        }
+        // Taken back when it's called, which happens exactly once
+        let engine = Box::into_raw(Box::new(Rc::clone(&self.inner)));
+        extern "C" fn callback(_: super::webview_t, arg: *mut c_void) {
+            let engine = unsafe { Box::from_raw(arg as *mut Rc<Engine>) };
+            engine.destroy();
+        }
+        unsafe { super::webview_dispatch(raw, Some(callback), engine as *mut _) }
+    }
+
     pub fn run(&mut self) {
-        unsafe { super::webview_run(*self.inner) }
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_run(w) }
+        }
     }
 
     pub fn terminate(&mut self) {
-        unsafe { super::webview_terminate(*self.inner) }
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_terminate(w) }
+        }
+    }
+
+    /// Ends the main loop every webview shares, even once they've all been destroyed.
+    pub fn terminate_loop() {
+        unsafe { super::webview_terminate_loop() }
     }
 
     // TODO Window instance
     pub fn set_title(&mut self, title: &str) {
         let c_title = CString::new(title).expect("No null bytes in parameter title");
-        unsafe { super::webview_set_title(*self.inner, c_title.as_ptr()) }
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_title(w, c_title.as_ptr()) }
+        }
     }
 
     pub fn set_size(&mut self, width: u16, height: u16, hints: SizeHint) {
-        unsafe { super::webview_set_size(*self.inner, width, height, hints as i32) }
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_size(w, width, height, hints as i32) }
+        }
+    }
+
+    /// Sets the smallest size the user can shrink the window to, `None` removing the limit.
+    pub fn set_min_size(&mut self, size: Option<(u16, u16)>) {
+        let (width, height) = size.unwrap_or_default();
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_min_size(w, width.into(), height.into()) }
+        }
+    }
+
+    /// Sets the largest size the user can grow the window to, `None` removing the limit.
+    pub fn set_max_size(&mut self, size: Option<(u16, u16)>) {
+        let (width, height) = size.unwrap_or_default();
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_max_size(w, width.into(), height.into()) }
+        }
+    }
+
+    /// Keeps the width divided by the height at `ratio` while resizing, `None` unlocking it.
+    pub fn set_aspect_ratio(&mut self, ratio: Option<f64>) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_aspect_ratio(w, ratio.unwrap_or_default()) }
+        }
+    }
+
+    /// Moves the window's top left corner to `x`, `y`. Ignored on Wayland, where the compositor
+    /// places windows itself.
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_position(w, x, y) }
+        }
+    }
+
+    pub fn set_maximized(&mut self, maximized: bool) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_maximized(w, maximized as c_int) }
+        }
+    }
+
+    pub fn set_minimized(&mut self, minimized: bool) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_minimized(w, minimized as c_int) }
+        }
+    }
+
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_fullscreen(w, fullscreen as c_int) }
+        }
+    }
+
+    /// Moves the window to the middle of the screen it's on.
+    pub fn center(&mut self) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_center(w) }
+        }
+    }
+
+    pub fn set_always_on_top(&mut self, on_top: bool) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_always_on_top(w, on_top as c_int) }
+        }
+    }
+
+    /// Shows or hides the window, the main loop keeps running while it's hidden.
+    pub fn set_visible(&mut self, visible: bool) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_visible(w, visible as c_int) }
+        }
+    }
+
+    /// Raises the window and gives it the keyboard focus.
+    pub fn focus(&mut self) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_focus(w) }
+        }
+    }
+
+    /// Shows or hides the title bar and borders.
+    pub fn set_decorations(&mut self, decorations: bool) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_decorated(w, decorations as c_int) }
+        }
+    }
+
+    pub fn geometry(&self) -> WindowGeometry {
+        let (mut x, mut y, mut width, mut height) = (0, 0, 0, 0);
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_get_geometry(w, &mut x, &mut y, &mut width, &mut height) }
+        }
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn state(&self) -> WindowState {
+        let state = self
+            .raw()
+            .map_or(0, |w| unsafe { super::webview_get_state(w) });
+        WindowState {
+            maximized: state & STATE_MAXIMIZED != 0,
+            minimized: state & STATE_MINIMIZED != 0,
+            fullscreen: state & STATE_FULLSCREEN != 0,
+            visible: state & STATE_VISIBLE != 0,
+            focused: state & STATE_FOCUSED != 0,
+        }
+    }
+
+    /// Calls `f` with every [`WindowEvent`], replacing the previous function. Its return value
+    /// only counts for `WindowEvent::CloseRequested`, where `false` keeps the window open.
+    /// `f` is never called on macOS yet.
+    pub fn on_window_event<F>(&mut self, f: F)
+    where
+        F: FnMut(WindowEvent) -> bool + 'static,
+    {
+        let Some(w) = self.raw() else {
+            return;
+        };
+        // Lives as long as the webview does, just like the bindings.
+        let closure = Box::into_raw(Box::new(f));
+        extern "C" fn callback<F>(event: c_int, a: c_int, b: c_int, arg: *mut c_void) -> c_int
+        where
+            F: FnMut(WindowEvent) -> bool + 'static,
+        {
+            let f = unsafe { &mut *(arg as *mut F) };
+            let events = match event {
+                EVENT_CLOSE_REQUESTED => return !f(WindowEvent::CloseRequested) as c_int,
+                EVENT_RESIZED => vec![WindowEvent::Resized {
+                    width: a,
+                    height: b,
+                }],
+                EVENT_MOVED => vec![WindowEvent::Moved { x: a, y: b }],
+                EVENT_FOCUSED => vec![WindowEvent::Focused(a != 0)],
+                EVENT_STATE_CHANGED => [
+                    (
+                        STATE_MAXIMIZED,
+                        WindowEvent::Maximized as fn(bool) -> WindowEvent,
+                    ),
+                    (STATE_MINIMIZED, WindowEvent::Minimized),
+                    (STATE_FULLSCREEN, WindowEvent::Fullscreen),
+                ]
+                .into_iter()
+                .filter(|(flag, _)| a & flag != 0)
+                .map(|(flag, event)| event(b & flag != 0))
+                .collect(),
+                EVENT_CLOSED => vec![WindowEvent::Closed],
+                _ => Vec::new(),
+            };
+            for event in events {
+                f(event);
+            }
+            0
+        }
+        self.keep(closure);
+        unsafe { super::webview_on_window_event(w, Some(callback::<F>), closure as *mut _) }
+    }
+
+    /// Asks `f` about every navigation the page makes, with the URL, the current page's URL,
+    /// what started it and whether the user did, replacing the previous function. It returns
+    /// whether the navigation may happen, allowed new windows open in this one. What's loaded
+    /// with [`Webview::navigate`] or [`Webview::set_html`] is always allowed. macOS doesn't ask
+    /// yet, everything is allowed there.
+    pub fn on_navigation<F>(&mut self, f: F)
+    where
+        F: FnMut(&str, &str, NavigationKind, bool) -> bool + 'static,
+    {
+        let Some(w) = self.raw() else {
+            return;
+        };
+        // Lives as long as the webview does, just like the bindings.
+        let closure = Box::into_raw(Box::new(f));
+        extern "C" fn callback<F>(
+            url: *const c_char,
+            current: *const c_char,
+            kind: c_int,
+            user_gesture: c_int,
+            arg: *mut c_void,
+        ) -> c_int
+        where
+            F: FnMut(&str, &str, NavigationKind, bool) -> bool + 'static,
+        {
+            let url = unsafe { CStr::from_ptr(url) }.to_string_lossy();
+            let current = unsafe { CStr::from_ptr(current) }.to_string_lossy();
+            // The webview_navigation_t values of lib.h
+            let kind = match kind {
+                0 => NavigationKind::Link,
+                1 => NavigationKind::NewWindow,
+                _ => NavigationKind::Other,
+            };
+            let f = unsafe { &mut *(arg as *mut F) };
+            f(&url, &current, kind, user_gesture != 0) as c_int
+        }
+        self.keep(closure);
+        unsafe { super::webview_on_navigation(w, Some(callback::<F>), closure as *mut _) }
+    }
+
+    /// Shows a file dialog, modal to the window, and returns right away. Once the user is done
+    /// `f` is called with the picked paths, or `None` if the dialog was cancelled.
+    ///
+    /// `default_name` is only used when saving. `filters` are made of a name and the patterns
+    /// of the files it shows, separated by `;`, e.g. `("Images", "*.png;*.jpg")`.
+    ///
+    /// macOS doesn't show it yet, `f` gets `None` right away.
+    #[allow(clippy::too_many_arguments)]
+    pub fn file_dialog<F>(
+        &mut self,
+        action: FileDialogAction,
+        multiple: bool,
+        title: Option<&str>,
+        default_name: Option<&str>,
+        default_folder: Option<&Path>,
+        filters: &[(&str, &str)],
+        f: F,
+    ) where
+        F: FnOnce(Option<Vec<PathBuf>>) + 'static,
+    {
+        let Some(w) = self.raw() else {
+            return f(None);
+        };
+        let c_string = |s: &[u8]| CString::new(s).ok();
+        let title = title.and_then(|title| c_string(title.as_bytes()));
+        let default_name = default_name.and_then(|name| c_string(name.as_bytes()));
+        let default_folder =
+            default_folder.and_then(|folder| c_string(folder.as_os_str().as_bytes()));
+        let (names, patterns): (Vec<_>, Vec<_>) = filters
+            .iter()
+            .filter_map(|(name, patterns)| {
+                Some((c_string(name.as_bytes())?, c_string(patterns.as_bytes())?))
+            })
+            .unzip();
+        let name_ptrs: Vec<*const c_char> = names.iter().map(|name| name.as_ptr()).collect();
+        let pattern_ptrs: Vec<*const c_char> = patterns.iter().map(|p| p.as_ptr()).collect();
+        let as_ptr = |s: &Option<CString>| s.as_ref().map_or(null(), |s| s.as_ptr());
+
+        // Taken back when it's called, which happens exactly once
+        let closure = Box::into_raw(Box::new(f));
+        extern "C" fn callback<F>(paths: *const *const c_char, count: c_int, arg: *mut c_void)
+        where
+            F: FnOnce(Option<Vec<PathBuf>>) + 'static,
+        {
+            let paths = usize::try_from(count).ok().map(|count| {
+                (0..count)
+                    .map(|i| {
+                        let path = unsafe { CStr::from_ptr(*paths.add(i)) };
+                        PathBuf::from(OsStr::from_bytes(path.to_bytes()))
+                    })
+                    .collect()
+            });
+            let f = unsafe { Box::from_raw(arg as *mut F) };
+            f(paths);
+        }
+        unsafe {
+            super::webview_file_dialog(
+                w,
+                action as c_int,
+                multiple as c_int,
+                as_ptr(&title),
+                as_ptr(&default_name),
+                as_ptr(&default_folder),
+                name_ptrs.as_ptr(),
+                pattern_ptrs.as_ptr(),
+                name_ptrs.len() as c_int,
+                Some(callback::<F>),
+                closure as *mut _,
+            )
+        }
+    }
+
+    /// Shows a message dialog, modal to the window, and returns right away. Once the user is
+    /// done `f` is called with the answer.
+    ///
+    /// Without `buttons`, it gets an OK button, along with a Cancel one for questions. The
+    /// first button is the default one. `checkbox` is the label and the initial state of a
+    /// checkbox, `input` the initial text of a text field.
+    ///
+    /// macOS doesn't show it yet, `f` gets a dismissed dialog's answer right away.
+    #[allow(clippy::too_many_arguments)]
+    pub fn message_dialog<F>(
+        &mut self,
+        level: MessageLevel,
+        title: Option<&str>,
+        message: &str,
+        buttons: &[&str],
+        checkbox: Option<(&str, bool)>,
+        input: Option<&str>,
+        f: F,
+    ) where
+        F: FnOnce(MessageResponse) + 'static,
+    {
+        let c_string = |s: &str| CString::new(s.replace('\0', "")).unwrap_or_default();
+        let as_ptr = |s: &Option<CString>| s.as_ref().map_or(null(), |s| s.as_ptr());
+        let title = title.map(c_string);
+        let message = c_string(message);
+        let buttons: Vec<CString> = buttons.iter().map(|button| c_string(button)).collect();
+        let button_ptrs: Vec<*const c_char> = buttons.iter().map(|b| b.as_ptr()).collect();
+        let label = checkbox.map(|(label, _)| c_string(label));
+        let checked = checkbox.is_some_and(|(_, checked)| checked);
+        let input = input.map(c_string);
+        let Some(w) = self.raw() else {
+            return f(MessageResponse {
+                button: None,
+                checked,
+                input: None,
+            });
+        };
+
+        // Taken back when it's called, which happens exactly once
+        let closure = Box::into_raw(Box::new(f));
+        extern "C" fn callback<F>(
+            button: c_int,
+            checked: c_int,
+            input: *const c_char,
+            arg: *mut c_void,
+        ) where
+            F: FnOnce(MessageResponse) + 'static,
+        {
+            let input = (!input.is_null()).then(|| {
+                unsafe { CStr::from_ptr(input) }
+                    .to_string_lossy()
+                    .into_owned()
+            });
+            let f = unsafe { Box::from_raw(arg as *mut F) };
+            f(MessageResponse {
+                button: usize::try_from(button).ok(),
+                checked: checked != 0,
+                input,
+            });
+        }
+        unsafe {
+            super::webview_message_dialog(
+                w,
+                level as c_int,
+                as_ptr(&title),
+                message.as_ptr(),
+                button_ptrs.as_ptr(),
+                button_ptrs.len() as c_int,
+                as_ptr(&label),
+                checked as c_int,
+                as_ptr(&input),
+                Some(callback::<F>),
+                closure as *mut _,
+            )
+        }
+    }
+
+    /// Shows the page's `alert()`, `confirm()` and `prompt()` as native message dialogs titled
+    /// with the window's title, instead of the engine's own ones titled with the page's URL.
+    /// Does nothing on macOS yet.
+    pub fn set_native_script_dialogs(&mut self, enabled: bool) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_native_script_dialogs(w, enabled as c_int) }
+        }
+    }
+
+    /// Returns the URL last given to [`Webview::navigate`] on this `Webview`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether closing the window ends the main loop, which it does by default. On macOS the
+    /// app ends with its last window for now, whatever this says.
+    pub fn set_exit_on_close(&mut self, exit: bool) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_exit_on_close(w, exit as c_int) }
+        }
+    }
+
+    /// Asks the window to close, which sends `WindowEvent::CloseRequested` first, or closes it
+    /// right away with `force`.
+    pub fn close(&mut self, force: bool) {
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_close(w, force as c_int) }
+        }
     }
 
     pub fn get_window(&self) -> *mut Window {
-        unsafe { super::webview_get_window(*self.inner) as *mut Window }
+        self.raw().map_or(null_mut(), |w| unsafe {
+            super::webview_get_window(w) as *mut Window
+        })
     }
 
     pub fn set_html(&mut self, html: &str) {
         let c_html = CString::new(html).expect("Failed to save HTML in the binary.");
-        unsafe { super::webview_set_html(*self.inner, c_html.as_ptr()) }
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_set_html(w, c_html.as_ptr()) }
+        }
     }
 
-    pub fn navigate(&mut self, url: &str) {
+    pub fn navigate(&mut self, url: &str) -> Result<(), QuarkError> {
+        crate::url::validate(url)?;
+        let c_url = CString::new(url).map_err(|_| QuarkError::InvalidUrl(url.to_string()))?;
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_navigate(w, c_url.as_ptr()) }
+        }
         self.url = url.to_string();
+        Ok(())
     }
 
     pub fn init(&mut self, js: &str) {
         let c_js = CString::new(js).expect("No null bytes in parameter js");
-        unsafe { super::webview_init(*self.inner, c_js.as_ptr()) }
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_init(w, c_js.as_ptr()) }
+        }
     }
 
     pub fn eval(&mut self, js: &str) {
         let c_js = CString::new(js).expect("No null bytes in parameter js");
-        unsafe { super::webview_eval(*self.inner, c_js.as_ptr()) }
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_eval(w, c_js.as_ptr()) }
+        }
     }
 
-    pub fn dispatch<F>(&mut self, f: F)
+    /// Evaluates `js`, then calls `f` with the value of its last expression, or
+    /// `QuarkError::JavaScriptError` holding the message of the exception it threw. Returns
+    /// right away. `undefined` and functions come back as `null`, promises as they are, not
+    /// waited for. On macOS `f` gets `QuarkError::Unsupported` for now.
+    pub fn eval_with_callback<F>(&mut self, js: &str, f: F)
     where
-        F: FnOnce(&mut Webview) + Send + 'static,
+        F: FnOnce(Result<serde_json::Value, QuarkError>) + 'static,
     {
+        let Ok(c_js) = CString::new(js) else {
+            return f(Err(QuarkError::JavaScriptError(
+                "The script holds a null byte".into(),
+            )));
+        };
+        if let Err(e) = supported("getting a script's result") {
+            return f(Err(e));
+        }
+        let Some(w) = self.raw() else {
+            return f(Err(QuarkError::JavaScriptError(
+                "The window has closed".into(),
+            )));
+        };
+        // Taken back when it's called, which happens exactly once
         let closure = Box::into_raw(Box::new(f));
-        extern "C" fn callback<F>(webview: super::webview_t, arg: *mut c_void)
+        extern "C" fn callback<F>(ok: c_int, result: *const c_char, arg: *mut c_void)
         where
-            F: FnOnce(&mut Webview) + Send + 'static,
+            F: FnOnce(Result<serde_json::Value, QuarkError>) + 'static,
         {
-            let mut webview = Webview {
-                inner: Rc::new(webview),
-                url: "".to_string(),
+            let result = unsafe { CStr::from_ptr(result) }.to_string_lossy();
+            let value = if ok != 0 {
+                serde_json::from_str(&result)
+                    .map_err(|e| QuarkError::JavaScriptError(format!("Invalid JSON: {e}")))
+            } else {
+                Err(QuarkError::JavaScriptError(result.into_owned()))
             };
-            let closure: Box<F> = unsafe { Box::from_raw(arg as *mut F) };
-            (*closure)(&mut webview);
+            let f = unsafe { Box::from_raw(arg as *mut F) };
+            f(value);
+        }
+        unsafe {
+            super::webview_eval_with_result(
+                w,
+                c_js.as_ptr(),
+                Some(callback::<F>),
+                closure as *mut _,
+            )
+        }
+    }
+
+    pub fn dispatch<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Webview) + Send + 'static,
+    {
+        if let Some(w) = self.raw() {
+            dispatch(w, f)
         }
-        unsafe { super::webview_dispatch(*self.inner, Some(callback::<F>), closure as *mut _) }
+    }
+
+    /// Returns a [`Dispatcher`] for posting closures to this webview from other threads.
+    pub fn dispatcher(&self) -> Dispatcher {
+        Dispatcher(self.inner.raw.get())
     }
 
     pub fn bind<F>(&mut self, name: &str, f: F)
     where
-        F: FnMut(&str, &str),
+        F: FnMut(&str, &str) + 'static,
     {
+        let Some(w) = self.raw() else {
+            return;
+        };
         let c_name = CString::new(name).expect("No null bytes in parameter name");
         let closure = Box::into_raw(Box::new(f));
         extern "C" fn callback<F>(seq: *const c_char, req: *const c_char, arg: *mut c_void)
         where
-            F: FnMut(&str, &str),
+            F: FnMut(&str, &str) + 'static,
         {
             let seq = unsafe {
                 CStr::from_ptr(seq)
@@ -137,14 +826,50 @@ impl Webview {
                     .to_str()
                     .expect("No null bytes in parameter req")
             };
-            let mut f: Box<F> = unsafe { Box::from_raw(arg as *mut F) };
-            (*f)(seq, req);
-            // mem::forget(f);
+            // The closure is reused for every call, so it must not be dropped here.
+            let f = unsafe { &mut *(arg as *mut F) };
+            f(seq, req);
+        }
+        self.keep(closure);
+        unsafe { super::webview_bind(w, c_name.as_ptr(), Some(callback::<F>), closure as *mut _) }
+    }
+
+    /// Serves every request made to `scheme://` with `handler`, which receives the path of the
+    /// requested URI and returns the file's contents and MIME type, or `None` if there's no such
+    /// file. Must be called before navigating to the scheme. Does nothing on macOS yet.
+    pub fn register_scheme<F>(&mut self, scheme: &str, handler: F)
+    where
+        F: Fn(&str) -> Option<(&'static [u8], &'static str)> + 'static,
+    {
+        let Some(w) = self.raw() else {
+            return;
+        };
+        let c_scheme = CString::new(scheme).expect("No null bytes in parameter scheme");
+        // Lives as long as the webview does, just like the bindings.
+        let closure = Box::into_raw(Box::new(handler));
+        extern "C" fn callback<F>(request: *mut c_void, path: *const c_char, arg: *mut c_void)
+        where
+            F: Fn(&str) -> Option<(&'static [u8], &'static str)> + 'static,
+        {
+            let path = unsafe { CStr::from_ptr(path) }.to_string_lossy();
+            let handler = unsafe { &*(arg as *const F) };
+            if let Some((data, mime_type)) = handler(&path) {
+                let c_mime_type = CString::new(mime_type).expect("No null bytes in the MIME type");
+                unsafe {
+                    super::webview_scheme_respond(
+                        request,
+                        data.as_ptr() as *const c_void,
+                        data.len(),
+                        c_mime_type.as_ptr(),
+                    )
+                }
+            }
         }
+        self.keep(closure);
         unsafe {
-            super::webview_bind(
-                *self.inner,
-                c_name.as_ptr(),
+            super::webview_register_scheme(
+                w,
+                c_scheme.as_ptr(),
                 Some(callback::<F>),
                 closure as *mut _,
             )
@@ -154,6 +879,8 @@ impl Webview {
     pub fn r#return(&self, seq: &str, status: c_int, result: &str) {
         let c_seq = CString::new(seq).expect("No null bytes in parameter seq");
         let c_result = CString::new(result).expect("No null bytes in parameter result");
-        unsafe { super::webview_return(*self.inner, c_seq.as_ptr(), status, c_result.as_ptr()) }
+        if let Some(w) = self.raw() {
+            unsafe { super::webview_return(w, c_seq.as_ptr(), status, c_result.as_ptr()) }
+        }
     }
 }
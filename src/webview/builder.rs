@@ -1,4 +1,7 @@
 use super::{SizeHint, Webview, Window};
+use crate::error::QuarkError;
+
+type DispatchFn = Box<dyn FnOnce(&mut Webview) + Send + 'static>;
 
 #[derive(Default)]
 pub struct WebviewBuilder<'a> {
@@ -9,8 +12,12 @@ pub struct WebviewBuilder<'a> {
     width: usize,
     height: usize,
     resize: SizeHint,
+    min_size: Option<(u16, u16)>,
+    max_size: Option<(u16, u16)>,
+    aspect_ratio: Option<f64>,
+    position: Option<(i32, i32)>,
     debug: bool,
-    dispatch: Option<Box<dyn FnOnce(&mut Webview) + Send + 'static>>,
+    dispatch: Option<DispatchFn>,
     window: Option<&'a mut Window>,
 }
 
@@ -64,6 +71,26 @@ impl<'a> WebviewBuilder<'a> {
         self
     }
 
+    pub fn min_size(mut self, width: u16, height: u16) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    pub fn max_size(mut self, width: u16, height: u16) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    pub fn aspect_ratio(mut self, ratio: f64) -> Self {
+        self.aspect_ratio = Some(ratio);
+        self
+    }
+
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
     pub fn dispatch<F>(mut self, f: F) -> Self
     where
         F: FnOnce(&mut Webview) + Send + 'static,
@@ -72,7 +99,7 @@ impl<'a> WebviewBuilder<'a> {
         self
     }
 
-    pub fn build(self) -> Webview {
+    pub fn build(self) -> Result<Webview, QuarkError> {
         let mut w = Webview::create(self.debug, self.window);
         if let Some(title) = self.title {
             w.set_title(title);
@@ -83,7 +110,7 @@ impl<'a> WebviewBuilder<'a> {
         }
 
         if let Some(url) = self.url {
-            w.navigate(url);
+            w.navigate(url)?;
         }
 
         if let Some(eval) = self.eval {
@@ -91,11 +118,24 @@ impl<'a> WebviewBuilder<'a> {
         }
 
         w.set_size(self.width as u16, self.height as u16, self.resize);
+        // After `set_size`, so they replace the bound a MIN or MAX hint set
+        if self.min_size.is_some() {
+            w.set_min_size(self.min_size);
+        }
+        if self.max_size.is_some() {
+            w.set_max_size(self.max_size);
+        }
+        if self.aspect_ratio.is_some() {
+            w.set_aspect_ratio(self.aspect_ratio);
+        }
+        if let Some((x, y)) = self.position {
+            w.set_position(x, y);
+        }
 
         if let Some(f) = self.dispatch {
             w.dispatch(f);
         }
 
-        w
+        Ok(w)
     }
 }
@@ -8,7 +8,10 @@
 
 #include <cstring>
 #include <functional>
+#include <set>
+#include <sstream>
 #include <string>
+#include <vector>
 
 namespace webview {
 namespace detail {
@@ -26,11 +29,23 @@ public:
     }
     g_signal_connect(G_OBJECT(m_window), "destroy",
                      G_CALLBACK(+[](GtkWidget *, gpointer arg) {
-                       static_cast<gtk_webkit_engine *>(arg)->terminate();
+                       auto *w = static_cast<gtk_webkit_engine *>(arg);
+                       w->window_event(WEBVIEW_EVENT_CLOSED, 0, 0);
+                       // The webview may outlive the engine while requests hold it
+                       g_object_set_data(G_OBJECT(w->m_webview), "quark-engine",
+                                         nullptr);
+                       w->m_window = nullptr;
+                       w->m_webview = nullptr;
+                       if (w->m_exit_on_close) {
+                         w->terminate();
+                       }
                      }),
                      this);
+    connect_window_events();
     // Initialize webview widget
     m_webview = webkit_web_view_new();
+    // Lets requests made to shared objects (custom schemes) find their engine
+    g_object_set_data(G_OBJECT(m_webview), "quark-engine", this);
     WebKitUserContentManager *manager =
         webkit_web_view_get_user_content_manager(WEBKIT_WEB_VIEW(m_webview));
     g_signal_connect(manager, "script-message-received::external",
@@ -53,9 +68,8 @@ public:
     WebKitSettings *settings =
         webkit_web_view_get_settings(WEBKIT_WEB_VIEW(m_webview));
     webkit_settings_set_javascript_can_access_clipboard(settings, true);
+    // The console goes to Rust through a binding, see console.rs
     if (debug) {
-      webkit_settings_set_enable_write_console_messages_to_stdout(settings,
-                                                                  true);
       webkit_settings_set_enable_developer_extras(settings, true);
     }
 
@@ -63,8 +77,12 @@ public:
   }
   virtual ~gtk_webkit_engine() = default;
   void *window() { return (void *)m_window; }
+  // Once the window is destroyed its widgets are gone, the methods do nothing
+  bool closed() const { return m_window == nullptr; }
   void run() { gtk_main(); }
-  void terminate() { gtk_main_quit(); }
+  void terminate() { terminate_loop(); }
+  // Every window shares the one main loop
+  static void terminate_loop() { gtk_main_quit(); }
   void dispatch(std::function<void()> f) {
     g_idle_add_full(G_PRIORITY_HIGH_IDLE, (GSourceFunc)([](void *f) -> int {
                       (*static_cast<dispatch_fn_t *>(f))();
@@ -75,37 +93,401 @@ public:
   }
 
   void set_title(const std::string &title) {
+    if (closed()) {
+      return;
+    }
     gtk_window_set_title(GTK_WINDOW(m_window), title.c_str());
   }
 
   void set_size(int width, int height, int hints) {
+    if (closed()) {
+      return;
+    }
     gtk_window_set_resizable(GTK_WINDOW(m_window), hints != WEBVIEW_HINT_FIXED);
     if (hints == WEBVIEW_HINT_NONE) {
       gtk_window_resize(GTK_WINDOW(m_window), width, height);
     } else if (hints == WEBVIEW_HINT_FIXED) {
       gtk_window_set_default_size(GTK_WINDOW(m_window), width, height);
       gtk_window_resize(GTK_WINDOW(m_window), width, height);
+    } else if (hints == WEBVIEW_HINT_MIN) {
+      set_min_size(width, height);
+    } else {
+      set_max_size(width, height);
+    }
+  }
+
+  void set_min_size(int width, int height) {
+    m_geometry.min_width = width;
+    m_geometry.min_height = height;
+    set_geometry_hint(GDK_HINT_MIN_SIZE, width > 0 && height > 0);
+  }
+
+  void set_max_size(int width, int height) {
+    m_geometry.max_width = width;
+    m_geometry.max_height = height;
+    set_geometry_hint(GDK_HINT_MAX_SIZE, width > 0 && height > 0);
+  }
+
+  void set_aspect_ratio(double ratio) {
+    m_geometry.min_aspect = m_geometry.max_aspect = ratio;
+    set_geometry_hint(GDK_HINT_ASPECT, ratio > 0);
+  }
+
+  // Wayland doesn't let windows place themselves, it's ignored there
+  void set_position(int x, int y) {
+    if (closed()) {
+      return;
+    }
+    gtk_window_move(GTK_WINDOW(m_window), x, y);
+  }
+
+  void set_maximized(bool maximized) {
+    if (closed()) {
+      return;
+    }
+    if (maximized) {
+      gtk_window_maximize(GTK_WINDOW(m_window));
+    } else {
+      gtk_window_unmaximize(GTK_WINDOW(m_window));
+    }
+  }
+
+  void set_minimized(bool minimized) {
+    if (closed()) {
+      return;
+    }
+    if (minimized) {
+      gtk_window_iconify(GTK_WINDOW(m_window));
+    } else {
+      gtk_window_deiconify(GTK_WINDOW(m_window));
+    }
+  }
+
+  void set_fullscreen(bool fullscreen) {
+    if (closed()) {
+      return;
+    }
+    if (fullscreen) {
+      gtk_window_fullscreen(GTK_WINDOW(m_window));
     } else {
-      GdkGeometry g;
-      g.min_width = g.max_width = width;
-      g.min_height = g.max_height = height;
-      GdkWindowHints h =
-          (hints == WEBVIEW_HINT_MIN ? GDK_HINT_MIN_SIZE : GDK_HINT_MAX_SIZE);
-      // This defines either MIN_SIZE, or MAX_SIZE, but not both:
-      gtk_window_set_geometry_hints(GTK_WINDOW(m_window), nullptr, &g, h);
+      gtk_window_unfullscreen(GTK_WINDOW(m_window));
+    }
+  }
+
+  // GTK_WIN_POS_CENTER only counts before the window is shown, which it
+  // already is, so it's moved by hand to the middle of its monitor
+  void center() {
+    if (closed()) {
+      return;
+    }
+    GdkWindow *window = gtk_widget_get_window(m_window);
+    if (window == nullptr) {
+      gtk_window_set_position(GTK_WINDOW(m_window), GTK_WIN_POS_CENTER);
+      return;
+    }
+    GdkMonitor *monitor =
+        gdk_display_get_monitor_at_window(gdk_window_get_display(window), window);
+    GdkRectangle area;
+    gdk_monitor_get_workarea(monitor, &area);
+    int width, height;
+    gtk_window_get_size(GTK_WINDOW(m_window), &width, &height);
+    gtk_window_move(GTK_WINDOW(m_window), area.x + (area.width - width) / 2,
+                    area.y + (area.height - height) / 2);
+  }
+
+  void set_always_on_top(bool on_top) {
+    if (closed()) {
+      return;
     }
+    gtk_window_set_keep_above(GTK_WINDOW(m_window), on_top);
+  }
+
+  void set_visible(bool visible) {
+    if (closed()) {
+      return;
+    }
+    if (visible) {
+      gtk_widget_show(m_window);
+    } else {
+      gtk_widget_hide(m_window);
+    }
+  }
+
+  void focus() {
+    if (closed()) {
+      return;
+    }
+    gtk_window_present(GTK_WINDOW(m_window));
+  }
+
+  void set_decorated(bool decorated) {
+    if (closed()) {
+      return;
+    }
+    gtk_window_set_decorated(GTK_WINDOW(m_window), decorated);
+  }
+
+  // Wayland keeps the position to itself, it's always 0, 0 there
+  void get_geometry(int *x, int *y, int *width, int *height) {
+    if (closed()) {
+      *x = *y = *width = *height = 0;
+      return;
+    }
+    gtk_window_get_position(GTK_WINDOW(m_window), x, y);
+    gtk_window_get_size(GTK_WINDOW(m_window), width, height);
+  }
+
+  int get_state() {
+    if (closed()) {
+      return 0;
+    }
+    int state = 0;
+    GdkWindow *window = gtk_widget_get_window(m_window);
+    if (window != nullptr) {
+      GdkWindowState gdk_state = gdk_window_get_state(window);
+      if (gdk_state & GDK_WINDOW_STATE_MAXIMIZED) {
+        state |= WEBVIEW_STATE_MAXIMIZED;
+      }
+      if (gdk_state & GDK_WINDOW_STATE_ICONIFIED) {
+        state |= WEBVIEW_STATE_MINIMIZED;
+      }
+      if (gdk_state & GDK_WINDOW_STATE_FULLSCREEN) {
+        state |= WEBVIEW_STATE_FULLSCREEN;
+      }
+    }
+    if (gtk_widget_get_visible(m_window)) {
+      state |= WEBVIEW_STATE_VISIBLE;
+    }
+    if (gtk_window_is_active(GTK_WINDOW(m_window))) {
+      state |= WEBVIEW_STATE_FOCUSED;
+    }
+    return state;
   }
 
   void navigate(const std::string &url) {
+    if (closed()) {
+      return;
+    }
+    m_app_navigation = true;
     webkit_web_view_load_uri(WEBKIT_WEB_VIEW(m_webview), url.c_str());
   }
 
   void set_html(const std::string &html) {
+    if (closed()) {
+      return;
+    }
+    m_app_navigation = true;
     webkit_web_view_load_html(WEBKIT_WEB_VIEW(m_webview), html.c_str(),
                               nullptr);
   }
 
+  using navigation_handler_t =
+      std::function<bool(const char *, const char *, int, bool)>;
+
+  void on_navigation(navigation_handler_t handler) {
+    if (closed()) {
+      return;
+    }
+    bool connect = !m_navigation_handler;
+    m_navigation_handler = handler;
+    if (connect) {
+      g_signal_connect(G_OBJECT(m_webview), "decide-policy",
+                       G_CALLBACK(decide_policy), this);
+    }
+  }
+
+  // Gets the picked paths, or NULL and -1 when the dialog was cancelled
+  using file_dialog_handler_t = std::function<void(const char *const *, int)>;
+
+  void file_dialog(int action, bool multiple, const char *title,
+                   const char *default_name, const char *default_folder,
+                   const char *const *filter_names,
+                   const char *const *filter_patterns, int filter_count,
+                   file_dialog_handler_t handler) {
+    if (closed()) {
+      handler(nullptr, -1);
+      return;
+    }
+    GtkFileChooserAction chooser_action = GTK_FILE_CHOOSER_ACTION_OPEN;
+    if (action == WEBVIEW_FILE_DIALOG_SAVE) {
+      chooser_action = GTK_FILE_CHOOSER_ACTION_SAVE;
+    } else if (action == WEBVIEW_FILE_DIALOG_FOLDER) {
+      chooser_action = GTK_FILE_CHOOSER_ACTION_SELECT_FOLDER;
+    }
+    // Goes through the desktop portal when there's one, sandboxed or not
+    GtkFileChooserNative *dialog = gtk_file_chooser_native_new(
+        title, GTK_WINDOW(m_window), chooser_action, nullptr, nullptr);
+    GtkFileChooser *chooser = GTK_FILE_CHOOSER(dialog);
+    gtk_native_dialog_set_modal(GTK_NATIVE_DIALOG(dialog), TRUE);
+    gtk_file_chooser_set_select_multiple(
+        chooser, multiple && action != WEBVIEW_FILE_DIALOG_SAVE);
+    gtk_file_chooser_set_do_overwrite_confirmation(chooser, TRUE);
+    if (default_folder != nullptr) {
+      gtk_file_chooser_set_current_folder(chooser, default_folder);
+    }
+    if (default_name != nullptr && action == WEBVIEW_FILE_DIALOG_SAVE) {
+      gtk_file_chooser_set_current_name(chooser, default_name);
+    }
+    for (int i = 0; i < filter_count; i++) {
+      GtkFileFilter *filter = gtk_file_filter_new();
+      gtk_file_filter_set_name(filter, filter_names[i]);
+      std::istringstream patterns(filter_patterns[i]);
+      std::string pattern;
+      while (std::getline(patterns, pattern, ';')) {
+        gtk_file_filter_add_pattern(filter, pattern.c_str());
+      }
+      gtk_file_chooser_add_filter(chooser, filter);
+    }
+
+    // Answered from the response signal instead of gtk_native_dialog_run, which
+    // would spin a nested main loop inside whatever binding showed the dialog.
+    // Freed along with the dialog once the user is done.
+    auto *pending = new file_dialog_handler_t(handler);
+    g_signal_connect(dialog, "response",
+                     G_CALLBACK(+[](GtkNativeDialog *dialog, int response,
+                                    gpointer arg) {
+                       auto *handler = static_cast<file_dialog_handler_t *>(arg);
+                       if (response == GTK_RESPONSE_ACCEPT) {
+                         GSList *paths = gtk_file_chooser_get_filenames(
+                             GTK_FILE_CHOOSER(dialog));
+                         std::vector<const char *> picked;
+                         for (GSList *path = paths; path != nullptr;
+                              path = path->next) {
+                           picked.push_back(static_cast<const char *>(path->data));
+                         }
+                         (*handler)(picked.data(), static_cast<int>(picked.size()));
+                         g_slist_free_full(paths, g_free);
+                       } else {
+                         (*handler)(nullptr, -1);
+                       }
+                       delete handler;
+                       g_object_unref(dialog);
+                     }),
+                     pending);
+    gtk_native_dialog_show(GTK_NATIVE_DIALOG(dialog));
+  }
+
+  // Gets the index of the button clicked, or -1 when the dialog was dismissed,
+  // whether the checkbox is checked and the text typed in, NULL without a text
+  // field or when the dialog was dismissed
+  using message_dialog_handler_t = std::function<void(int, bool, const char *)>;
+
+  void message_dialog(int level, const char *title, const char *message,
+                      const char *const *buttons, int button_count,
+                      const char *checkbox, bool checked, const char *input,
+                      message_dialog_handler_t handler) {
+    if (closed()) {
+      handler(-1, checked, nullptr);
+      return;
+    }
+    GtkMessageType type = GTK_MESSAGE_INFO;
+    switch (level) {
+    case WEBVIEW_MESSAGE_WARNING:
+      type = GTK_MESSAGE_WARNING;
+      break;
+    case WEBVIEW_MESSAGE_ERROR:
+      type = GTK_MESSAGE_ERROR;
+      break;
+    case WEBVIEW_MESSAGE_QUESTION:
+      type = GTK_MESSAGE_QUESTION;
+      break;
+    }
+    GtkButtonsType stock = GTK_BUTTONS_NONE;
+    if (button_count == 0) {
+      stock = level == WEBVIEW_MESSAGE_QUESTION ? GTK_BUTTONS_OK_CANCEL
+                                                : GTK_BUTTONS_OK;
+    }
+    GtkWidget *dialog = gtk_message_dialog_new(
+        GTK_WINDOW(m_window),
+        GtkDialogFlags(GTK_DIALOG_MODAL | GTK_DIALOG_DESTROY_WITH_PARENT), type,
+        stock, "%s", message);
+    if (title != nullptr) {
+      gtk_window_set_title(GTK_WINDOW(dialog), title);
+    }
+    // The buttons' response ids are their indices, stock ones are negative
+    for (int i = 0; i < button_count; i++) {
+      gtk_dialog_add_button(GTK_DIALOG(dialog), buttons[i], i);
+    }
+    gtk_dialog_set_default_response(GTK_DIALOG(dialog),
+                                    button_count > 0 ? 0 : GTK_RESPONSE_OK);
+
+    GtkWidget *area =
+        gtk_message_dialog_get_message_area(GTK_MESSAGE_DIALOG(dialog));
+    GtkWidget *entry = nullptr;
+    if (input != nullptr) {
+      entry = gtk_entry_new();
+      gtk_entry_set_text(GTK_ENTRY(entry), input);
+      gtk_entry_set_activates_default(GTK_ENTRY(entry), TRUE);
+      gtk_container_add(GTK_CONTAINER(area), entry);
+    }
+    GtkWidget *check = nullptr;
+    if (checkbox != nullptr) {
+      check = gtk_check_button_new_with_label(checkbox);
+      gtk_toggle_button_set_active(GTK_TOGGLE_BUTTON(check), checked);
+      gtk_container_add(GTK_CONTAINER(area), check);
+    }
+    gtk_widget_show_all(area);
+
+    // Answered from the response signal instead of gtk_dialog_run, which would
+    // spin a nested main loop inside whatever binding showed the dialog. Freed
+    // once the dialog is destroyed, which happens along with the window too.
+    auto *pending = new pending_message_t{handler, check, entry, checked};
+    g_signal_connect(dialog, "response",
+                     G_CALLBACK(+[](GtkDialog *dialog, int response, gpointer arg) {
+                       auto *pending = static_cast<pending_message_t *>(arg);
+                       int button = -1;
+                       if (response >= 0) {
+                         button = response;
+                       } else if (response == GTK_RESPONSE_OK) {
+                         button = 0;
+                       } else if (response == GTK_RESPONSE_CANCEL) {
+                         button = 1;
+                       }
+                       if (pending->check != nullptr) {
+                         pending->checked = gtk_toggle_button_get_active(
+                             GTK_TOGGLE_BUTTON(pending->check));
+                       }
+                       const char *text = nullptr;
+                       if (pending->entry != nullptr && button != -1) {
+                         text = gtk_entry_get_text(GTK_ENTRY(pending->entry));
+                       }
+                       auto handler = std::move(pending->handler);
+                       pending->handler = nullptr;
+                       handler(button, pending->checked, text);
+                       gtk_widget_destroy(GTK_WIDGET(dialog));
+                     }),
+                     pending);
+    g_signal_connect(dialog, "destroy",
+                     G_CALLBACK(+[](GtkWidget *, gpointer arg) {
+                       auto *pending = static_cast<pending_message_t *>(arg);
+                       // Gone with its window before the user answered
+                       if (pending->handler) {
+                         pending->handler(-1, pending->checked, nullptr);
+                       }
+                       delete pending;
+                     }),
+                     pending);
+    gtk_widget_show(dialog);
+  }
+
+  void set_native_script_dialogs(bool enabled) {
+    if (closed()) {
+      return;
+    }
+    if (enabled && m_script_dialog_handler == 0) {
+      m_script_dialog_handler =
+          g_signal_connect(G_OBJECT(m_webview), "script-dialog",
+                           G_CALLBACK(script_dialog), this);
+    } else if (!enabled && m_script_dialog_handler != 0) {
+      g_signal_handler_disconnect(G_OBJECT(m_webview), m_script_dialog_handler);
+      m_script_dialog_handler = 0;
+    }
+  }
+
   void init(const std::string &js) {
+    if (closed()) {
+      return;
+    }
     WebKitUserContentManager *manager =
         webkit_web_view_get_user_content_manager(WEBKIT_WEB_VIEW(m_webview));
     webkit_user_content_manager_add_script(
@@ -116,13 +498,330 @@ public:
   }
 
   void eval(const std::string &js) {
+    if (closed()) {
+      return;
+    }
     webkit_web_view_run_javascript(WEBKIT_WEB_VIEW(m_webview), js.c_str(),
                                    nullptr, nullptr, nullptr);
   }
 
+  using eval_handler_t = std::function<void(bool, const char *)>;
+
+  void eval_with_result(const std::string &js, eval_handler_t handler) {
+    if (closed()) {
+      handler(false, "The window has closed");
+      return;
+    }
+    // Freed by eval_finished
+    webkit_web_view_run_javascript(WEBKIT_WEB_VIEW(m_webview), js.c_str(),
+                                   nullptr, eval_finished,
+                                   new eval_handler_t(handler));
+  }
+
+  using window_event_handler_t = std::function<int(int, int, int)>;
+
+  void on_window_event(window_event_handler_t handler) {
+    m_window_event_handler = handler;
+  }
+
+  void set_exit_on_close(bool exit) { m_exit_on_close = exit; }
+
+  // Asks the window to close the way its close button does, so it can be
+  // vetoed, or destroys it right away when forced
+  void close(bool force) {
+    if (closed()) {
+      return;
+    }
+    if (force) {
+      gtk_widget_destroy(m_window);
+    } else {
+      gtk_window_close(GTK_WINDOW(m_window));
+    }
+  }
+
+  using scheme_handler_t = std::function<void(void *, const std::string &)>;
+
+  void register_scheme(const std::string &scheme, scheme_handler_t handler) {
+    if (closed()) {
+      return;
+    }
+    m_scheme_handlers[scheme] = handler;
+
+    // Every webview shares the default web context, so the scheme is only
+    // registered once and requests are routed back to the webview that made
+    // them.
+    static std::set<std::string> registered;
+    if (!registered.insert(scheme).second) {
+      return;
+    }
+    WebKitWebContext *context =
+        webkit_web_view_get_context(WEBKIT_WEB_VIEW(m_webview));
+    webkit_web_context_register_uri_scheme(
+        context, scheme.c_str(),
+        +[](WebKitURISchemeRequest *request, gpointer) {
+          const char *path = webkit_uri_scheme_request_get_path(request);
+          if (path == nullptr) {
+            path = "/";
+          }
+          auto *w = static_cast<gtk_webkit_engine *>(g_object_get_data(
+              G_OBJECT(webkit_uri_scheme_request_get_web_view(request)),
+              "quark-engine"));
+          if (w != nullptr) {
+            auto found = w->m_scheme_handlers.find(
+                webkit_uri_scheme_request_get_scheme(request));
+            if (found != w->m_scheme_handlers.end()) {
+              found->second(request, path);
+            }
+          }
+          if (g_object_get_data(G_OBJECT(request), "quark-responded") ==
+              nullptr) {
+            GError *error = g_error_new(G_IO_ERROR, G_IO_ERROR_NOT_FOUND,
+                                        "%s not found", path);
+            webkit_uri_scheme_request_finish_error(request, error);
+            g_error_free(error);
+          }
+        },
+        nullptr, nullptr);
+
+    WebKitSecurityManager *security =
+        webkit_web_context_get_security_manager(context);
+    webkit_security_manager_register_uri_scheme_as_secure(security,
+                                                          scheme.c_str());
+    webkit_security_manager_register_uri_scheme_as_cors_enabled(
+        security, scheme.c_str());
+  }
+
+  static void scheme_respond(void *request, const void *data, size_t length,
+                             const std::string &mime_type) {
+    auto *r = WEBKIT_URI_SCHEME_REQUEST(request);
+    if (g_object_get_data(G_OBJECT(r), "quark-responded") != nullptr) {
+      return;
+    }
+    g_object_set_data(G_OBJECT(r), "quark-responded", GINT_TO_POINTER(1));
+
+    GBytes *bytes = g_bytes_new(data, length);
+    GInputStream *stream = g_memory_input_stream_new_from_bytes(bytes);
+    webkit_uri_scheme_request_finish(r, stream, static_cast<gint64>(length),
+                                     mime_type.c_str());
+    g_object_unref(stream);
+    g_bytes_unref(bytes);
+  }
+
 private:
   virtual void on_message(const std::string& msg) = 0;
 
+  static gboolean decide_policy(WebKitWebView *webview,
+                                WebKitPolicyDecision *decision,
+                                WebKitPolicyDecisionType type, gpointer arg) {
+    auto *w = static_cast<gtk_webkit_engine *>(arg);
+    if (type == WEBKIT_POLICY_DECISION_TYPE_RESPONSE) {
+      return FALSE;
+    }
+    WebKitNavigationAction *action =
+        webkit_navigation_policy_decision_get_navigation_action(
+            WEBKIT_NAVIGATION_POLICY_DECISION(decision));
+
+    // What the application loads itself is always allowed, along with the
+    // redirects it leads to
+    bool redirect = webkit_navigation_action_is_redirect(action);
+    bool app = w->m_app_navigation || (w->m_app_redirects && redirect);
+    w->m_app_navigation = false;
+    w->m_app_redirects = app;
+    if (app && type == WEBKIT_POLICY_DECISION_TYPE_NAVIGATION_ACTION) {
+      webkit_policy_decision_use(decision);
+      return TRUE;
+    }
+
+    std::string uri =
+        webkit_uri_request_get_uri(webkit_navigation_action_get_request(action));
+    const char *current = webkit_web_view_get_uri(webview);
+    int kind = WEBVIEW_NAVIGATION_OTHER;
+    if (type == WEBKIT_POLICY_DECISION_TYPE_NEW_WINDOW_ACTION) {
+      kind = WEBVIEW_NAVIGATION_NEW_WINDOW;
+    } else if (webkit_navigation_action_get_navigation_type(action) ==
+               WEBKIT_NAVIGATION_TYPE_LINK_CLICKED) {
+      kind = WEBVIEW_NAVIGATION_LINK;
+    }
+    bool allowed =
+        w->m_navigation_handler(uri.c_str(), current != nullptr ? current : "",
+                                kind,
+                                webkit_navigation_action_is_user_gesture(action));
+
+    if (allowed && type == WEBKIT_POLICY_DECISION_TYPE_NAVIGATION_ACTION) {
+      webkit_policy_decision_use(decision);
+      return TRUE;
+    }
+    webkit_policy_decision_ignore(decision);
+    // There's only ever one window per webview, so an allowed new window
+    // opens in this one
+    if (allowed) {
+      w->navigate(uri);
+    }
+    return TRUE;
+  }
+
+  static void eval_finished(GObject *object, GAsyncResult *result,
+                            gpointer arg) {
+    auto *handler = static_cast<eval_handler_t *>(arg);
+    GError *error = nullptr;
+    WebKitJavascriptResult *js_result = webkit_web_view_run_javascript_finish(
+        WEBKIT_WEB_VIEW(object), result, &error);
+    if (js_result == nullptr) {
+      (*handler)(false, error->message);
+      g_error_free(error);
+      delete handler;
+      return;
+    }
+
+    JSCValue *value = webkit_javascript_result_get_js_value(js_result);
+    char *json = nullptr;
+    if (!jsc_value_is_undefined(value)) {
+      json = jsc_value_to_json(value, 0);
+    }
+    JSCContext *context = jsc_value_get_context(value);
+    JSCException *exception = jsc_context_get_exception(context);
+    if (exception != nullptr) {
+      // Thrown by a toJSON or on a cycle
+      (*handler)(false, jsc_exception_get_message(exception));
+      jsc_context_clear_exception(context);
+    } else {
+      // Functions and symbols have no JSON either
+      (*handler)(true, json != nullptr ? json : "null");
+    }
+    g_free(json);
+    webkit_javascript_result_unref(js_result);
+    delete handler;
+  }
+
+  // Answered once the user is done, the page waits meanwhile without a nested
+  // main loop on this side
+  static gboolean script_dialog(WebKitWebView *, WebKitScriptDialog *dialog,
+                                gpointer arg) {
+    auto *w = static_cast<gtk_webkit_engine *>(arg);
+    const char *title = gtk_window_get_title(GTK_WINDOW(w->m_window));
+    const char *message = webkit_script_dialog_get_message(dialog);
+    int level = WEBVIEW_MESSAGE_QUESTION;
+    const char *input = nullptr;
+    switch (webkit_script_dialog_get_dialog_type(dialog)) {
+    case WEBKIT_SCRIPT_DIALOG_ALERT:
+      level = WEBVIEW_MESSAGE_INFO;
+      break;
+    case WEBKIT_SCRIPT_DIALOG_PROMPT:
+      input = webkit_script_dialog_prompt_get_default_text(dialog);
+      break;
+    default:
+      break;
+    }
+    webkit_script_dialog_ref(dialog);
+    w->message_dialog(
+        level, title, message, nullptr, 0, nullptr, false, input,
+        [dialog](int button, bool, const char *text) {
+          switch (webkit_script_dialog_get_dialog_type(dialog)) {
+          case WEBKIT_SCRIPT_DIALOG_CONFIRM:
+          case WEBKIT_SCRIPT_DIALOG_BEFORE_UNLOAD_CONFIRM:
+            webkit_script_dialog_confirm_set_confirmed(dialog, button == 0);
+            break;
+          case WEBKIT_SCRIPT_DIALOG_PROMPT:
+            // Left unset, prompt() returns null
+            if (button == 0 && text != nullptr) {
+              webkit_script_dialog_prompt_set_text(dialog, text);
+            }
+            break;
+          default:
+            break;
+          }
+          webkit_script_dialog_close(dialog);
+          webkit_script_dialog_unref(dialog);
+        });
+    return TRUE;
+  }
+
+  int window_event(int event, int a, int b) {
+    return m_window_event_handler ? m_window_event_handler(event, a, b) : 0;
+  }
+
+  void connect_window_events() {
+    // Returning TRUE keeps the window open
+    g_signal_connect(G_OBJECT(m_window), "delete-event",
+                     G_CALLBACK(+[](GtkWidget *, GdkEvent *, gpointer arg) {
+                       auto *w = static_cast<gtk_webkit_engine *>(arg);
+                       return static_cast<gboolean>(
+                           w->window_event(WEBVIEW_EVENT_CLOSE_REQUESTED, 0, 0) != 0);
+                     }),
+                     this);
+    // Fires for every change of position or size, only the changes are sent
+    g_signal_connect(G_OBJECT(m_window), "configure-event",
+                     G_CALLBACK(+[](GtkWidget *window, GdkEvent *, gpointer arg) {
+                       auto *w = static_cast<gtk_webkit_engine *>(arg);
+                       int x, y, width, height;
+                       gtk_window_get_position(GTK_WINDOW(window), &x, &y);
+                       gtk_window_get_size(GTK_WINDOW(window), &width, &height);
+                       if (width != w->m_width || height != w->m_height) {
+                         w->m_width = width;
+                         w->m_height = height;
+                         w->window_event(WEBVIEW_EVENT_RESIZED, width, height);
+                       }
+                       if (x != w->m_x || y != w->m_y) {
+                         w->m_x = x;
+                         w->m_y = y;
+                         w->window_event(WEBVIEW_EVENT_MOVED, x, y);
+                       }
+                       return FALSE;
+                     }),
+                     this);
+    g_signal_connect(G_OBJECT(m_window), "focus-in-event",
+                     G_CALLBACK(+[](GtkWidget *, GdkEvent *, gpointer arg) {
+                       static_cast<gtk_webkit_engine *>(arg)->window_event(
+                           WEBVIEW_EVENT_FOCUSED, 1, 0);
+                       return FALSE;
+                     }),
+                     this);
+    g_signal_connect(G_OBJECT(m_window), "focus-out-event",
+                     G_CALLBACK(+[](GtkWidget *, GdkEvent *, gpointer arg) {
+                       static_cast<gtk_webkit_engine *>(arg)->window_event(
+                           WEBVIEW_EVENT_FOCUSED, 0, 0);
+                       return FALSE;
+                     }),
+                     this);
+    g_signal_connect(
+        G_OBJECT(m_window), "window-state-event",
+        G_CALLBACK(+[](GtkWidget *, GdkEventWindowState *e, gpointer arg) {
+          auto flags = [](int gdk_state) {
+            int state = 0;
+            if (gdk_state & GDK_WINDOW_STATE_MAXIMIZED) {
+              state |= WEBVIEW_STATE_MAXIMIZED;
+            }
+            if (gdk_state & GDK_WINDOW_STATE_ICONIFIED) {
+              state |= WEBVIEW_STATE_MINIMIZED;
+            }
+            if (gdk_state & GDK_WINDOW_STATE_FULLSCREEN) {
+              state |= WEBVIEW_STATE_FULLSCREEN;
+            }
+            return state;
+          };
+          int changed = flags(e->changed_mask);
+          if (changed != 0) {
+            static_cast<gtk_webkit_engine *>(arg)->window_event(
+                WEBVIEW_EVENT_STATE_CHANGED, changed,
+                flags(e->new_window_state));
+          }
+          return FALSE;
+        }),
+        this);
+  }
+
+  // GTK only keeps the hints from the latest call, so every one of them is
+  // kept here and they're all set together
+  void set_geometry_hint(int hint, bool enabled) {
+    if (closed()) {
+      return;
+    }
+    m_geometry_hints = enabled ? (m_geometry_hints | hint)
+                               : (m_geometry_hints & ~hint);
+    gtk_window_set_geometry_hints(GTK_WINDOW(m_window), nullptr, &m_geometry,
+                                  static_cast<GdkWindowHints>(m_geometry_hints));
+  }
+
   static char* get_string_from_js_result(WebKitJavascriptResult* r) {
     char* s;
     JSGlobalContextRef ctx = webkit_javascript_result_get_global_context(r);
@@ -135,8 +834,27 @@ private:
     return s;
   }
 
+  // A message dialog waiting for the user
+  struct pending_message_t {
+    message_dialog_handler_t handler;
+    GtkWidget *check;
+    GtkWidget *entry;
+    bool checked;
+  };
+
   GtkWidget* m_window;
   GtkWidget* m_webview;
+  std::map<std::string, scheme_handler_t> m_scheme_handlers;
+  GdkGeometry m_geometry{};
+  int m_geometry_hints = 0;
+  window_event_handler_t m_window_event_handler;
+  bool m_exit_on_close = true;
+  navigation_handler_t m_navigation_handler;
+  bool m_app_navigation = false;
+  bool m_app_redirects = false;
+  gulong m_script_dialog_handler = 0;
+  // The last geometry sent with a window event
+  int m_x = 0, m_y = 0, m_width = 0, m_height = 0;
 };
 
 } // namespace detail
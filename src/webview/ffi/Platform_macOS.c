@@ -33,7 +33,8 @@ namespace webview {
             NSWindowStyleMaskTitled = 1,
             NSWindowStyleMaskClosable = 2,
             NSWindowStyleMaskMiniaturizable = 4,
-            NSWindowStyleMaskResizable = 8
+            NSWindowStyleMaskResizable = 8,
+            NSWindowStyleMaskFullScreen = 1 << 14
         };
 
         enum NSApplicationActivationPolicy : NSInteger {
@@ -82,7 +83,8 @@ namespace webview {
             }
             virtual ~cocoa_wkwebview_engine() = default;
             void* window() { return (void*)m_window; }
-            void terminate() {
+            void terminate() { terminate_loop(); }
+            static void terminate_loop() {
                 auto app = get_shared_application();
                 objc::msg_send<void>(app, "terminate:"_sel, nullptr);
             }
@@ -128,6 +130,99 @@ namespace webview {
                 }
                 objc::msg_send<void>(m_window, "center"_sel);
             }
+            void set_min_size(int width, int height) {
+                objc::msg_send<void>(m_window, "setContentMinSize:"_sel,
+                    CGSizeMake(width, height));
+            }
+            void set_max_size(int width, int height) {
+                // Zero means unbounded
+                objc::msg_send<void>(m_window, "setContentMaxSize:"_sel,
+                    CGSizeMake(width > 0 ? width : CGFLOAT_MAX,
+                        height > 0 ? height : CGFLOAT_MAX));
+            }
+            void set_aspect_ratio(double ratio) {
+                if (ratio > 0) {
+                    objc::msg_send<void>(m_window, "setContentAspectRatio:"_sel,
+                        CGSizeMake(ratio, 1));
+                }
+                else {
+                    // Resize increments replace the aspect ratio
+                    objc::msg_send<void>(m_window, "setContentResizeIncrements:"_sel,
+                        CGSizeMake(1, 1));
+                }
+            }
+            void set_position(int x, int y) {
+                // AppKit counts from the bottom left of the main screen
+                auto height = CGDisplayPixelsHigh(CGMainDisplayID());
+                objc::msg_send<void>(m_window, "setFrameTopLeftPoint:"_sel,
+                    CGPointMake(x, height - y));
+            }
+            void set_maximized(bool maximized) {
+                if (objc::msg_send<BOOL>(m_window, "isZoomed"_sel) != maximized) {
+                    objc::msg_send<void>(m_window, "zoom:"_sel, nullptr);
+                }
+            }
+            void set_minimized(bool minimized) {
+                if (minimized) {
+                    objc::msg_send<void>(m_window, "miniaturize:"_sel, nullptr);
+                }
+                else {
+                    objc::msg_send<void>(m_window, "deminiaturize:"_sel, nullptr);
+                }
+            }
+            void set_fullscreen(bool fullscreen) {
+                if (is_fullscreen() != fullscreen) {
+                    objc::msg_send<void>(m_window, "toggleFullScreen:"_sel, nullptr);
+                }
+            }
+            void center() {
+                objc::msg_send<void>(m_window, "center"_sel);
+            }
+            void set_always_on_top(bool on_top) {
+                // NSFloatingWindowLevel and NSNormalWindowLevel
+                objc::msg_send<void>(m_window, "setLevel:"_sel,
+                    static_cast<NSInteger>(on_top ? 3 : 0));
+            }
+            void set_visible(bool visible) {
+                if (visible) {
+                    objc::msg_send<void>(m_window, "orderFront:"_sel, nullptr);
+                }
+                else {
+                    objc::msg_send<void>(m_window, "orderOut:"_sel, nullptr);
+                }
+            }
+            void focus() {
+                objc::msg_send<void>(m_window, "makeKeyAndOrderFront:"_sel, nullptr);
+            }
+            void set_decorated(bool decorated) {
+                auto style = objc::msg_send<NSUInteger>(m_window, "styleMask"_sel);
+                style = decorated ? (style | NSWindowStyleMaskTitled)
+                    : (style & ~NSWindowStyleMaskTitled);
+                objc::msg_send<void>(m_window, "setStyleMask:"_sel, style);
+            }
+            // TODO: `frame` returns a struct, which needs objc_msgSend_stret on x86_64
+            void get_geometry(int* x, int* y, int* width, int* height) {
+                *x = *y = *width = *height = 0;
+            }
+            int get_state() {
+                int state = 0;
+                if (objc::msg_send<BOOL>(m_window, "isZoomed"_sel)) {
+                    state |= WEBVIEW_STATE_MAXIMIZED;
+                }
+                if (objc::msg_send<BOOL>(m_window, "isMiniaturized"_sel)) {
+                    state |= WEBVIEW_STATE_MINIMIZED;
+                }
+                if (is_fullscreen()) {
+                    state |= WEBVIEW_STATE_FULLSCREEN;
+                }
+                if (objc::msg_send<BOOL>(m_window, "isVisible"_sel)) {
+                    state |= WEBVIEW_STATE_VISIBLE;
+                }
+                if (objc::msg_send<BOOL>(m_window, "isKeyWindow"_sel)) {
+                    state |= WEBVIEW_STATE_FOCUSED;
+                }
+                return state;
+            }
             void navigate(const std::string& url) {
                 auto nsurl = objc::msg_send<id>(
                     "NSURL"_cls, "URLWithString:"_sel,
@@ -168,7 +263,83 @@ namespace webview {
                     nullptr);
             }
 
+            using eval_handler_t = std::function<void(bool, const char*)>;
+
+            // TODO: evaluateJavaScript:completionHandler: wants a block, and the result
+            // turned into JSON with NSJSONSerialization. Rust fails with
+            // QuarkError::Unsupported before getting here until then.
+            void eval_with_result(const std::string& /*js*/, eval_handler_t handler) {
+                handler(false, "Getting results back isn't supported on macOS yet");
+            }
+
+            using window_event_handler_t = std::function<int(int, int, int)>;
+
+            // TODO: an NSWindowDelegate (windowShouldClose:, windowDidResize:, ...) to send
+            // the window events through. Quark's handlers fail with QuarkError::Unsupported
+            // until then, the handler set here is never called.
+            void on_window_event(window_event_handler_t /*handler*/) {}
+            // TODO: goes with the window delegate, applicationShouldTerminateAfterLastWindowClosed:
+            // decides for now
+            void set_exit_on_close(bool /*exit*/) {}
+            void close(bool force) {
+                if (force) {
+                    objc::msg_send<void>(m_window, "close"_sel);
+                }
+                else {
+                    objc::msg_send<void>(m_window, "performClose:"_sel, nullptr);
+                }
+            }
+
+            using navigation_handler_t =
+                std::function<bool(const char*, const char*, int, bool)>;
+
+            // TODO: a WKNavigationDelegate (decidePolicyForNavigationAction:) to ask it.
+            // Everything is allowed until then, Quark refuses windows with a policy set and
+            // warns about the others.
+            void on_navigation(navigation_handler_t /*handler*/) {}
+
+            using file_dialog_handler_t = std::function<void(const char* const*, int)>;
+
+            // TODO: NSOpenPanel and NSSavePanel, run as sheets of the window. Rust fails
+            // with QuarkError::Unsupported before getting here until then, this only keeps
+            // direct callers from waiting forever.
+            void file_dialog(int /*action*/, bool /*multiple*/, const char* /*title*/,
+                             const char* /*default_name*/, const char* /*default_folder*/,
+                             const char* const* /*filter_names*/,
+                             const char* const* /*filter_patterns*/, int /*filter_count*/,
+                             file_dialog_handler_t handler) {
+                handler(nullptr, -1);
+            }
+
+            using message_dialog_handler_t = std::function<void(int, bool, const char*)>;
+
+            // TODO: NSAlert, with accessoryView holding the checkbox and the text field. Same
+            // as file_dialog until then, Rust fails first and this answers as dismissed.
+            void message_dialog(int /*level*/, const char* /*title*/, const char* /*message*/,
+                                const char* const* /*buttons*/, int /*button_count*/,
+                                const char* /*checkbox*/, bool checked,
+                                const char* /*input*/, message_dialog_handler_t handler) {
+                handler(-1, checked, nullptr);
+            }
+            // TODO: goes with the WKUIDelegate (runJavaScriptAlertPanelWithMessage:, ...),
+            // QuarkConfig refuses to turn it on until then
+            void set_native_script_dialogs(bool /*enabled*/) {}
+
+            using scheme_handler_t = std::function<void(void*, const std::string&)>;
+
+            // TODO: WKURLSchemeHandler, it has to be set on the WKWebViewConfiguration before
+            // the webview gets created, so this needs some shuffling around first.
+            void register_scheme(const std::string& /*scheme*/, scheme_handler_t /*handler*/) {}
+
+            static void scheme_respond(void* /*request*/, const void* /*data*/,
+                size_t /*length*/, const std::string& /*mime_type*/) {
+            }
+
         private:
+            bool is_fullscreen() {
+                auto style = objc::msg_send<NSUInteger>(m_window, "styleMask"_sel);
+                return (style & NSWindowStyleMaskFullScreen) != 0;
+            }
             virtual void on_message(const std::string& msg) = 0;
             id create_app_delegate() {
                 // Note: Avoid registering the class name "AppDelegate" as it is the
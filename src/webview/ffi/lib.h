@@ -23,6 +23,61 @@ typedef enum {
   WEBVIEW_HINT_FIXED
 } webview_hint_t;
 
+// Window state flags, see webview_get_state
+typedef enum {
+  WEBVIEW_STATE_MAXIMIZED = 1 << 0,
+  WEBVIEW_STATE_MINIMIZED = 1 << 1,
+  WEBVIEW_STATE_FULLSCREEN = 1 << 2,
+  WEBVIEW_STATE_VISIBLE = 1 << 3,
+  WEBVIEW_STATE_FOCUSED = 1 << 4
+} webview_state_t;
+
+// Window events, see webview_on_window_event
+typedef enum {
+  /// The user asked to close the window, it can be kept open.
+  WEBVIEW_EVENT_CLOSE_REQUESTED,
+  /// The window was resized, a and b are the new width and height.
+  WEBVIEW_EVENT_RESIZED,
+  /// The window was moved, a and b are the new x and y.
+  WEBVIEW_EVENT_MOVED,
+  /// The window gained the focus if a is non-zero, lost it otherwise.
+  WEBVIEW_EVENT_FOCUSED,
+  /// a holds the WEBVIEW_STATE flags that changed, b the new ones.
+  WEBVIEW_EVENT_STATE_CHANGED,
+  /// The window is gone, nothing but webview_dispatch and webview_destroy may be
+  /// called after it. Functions already dispatched run before one dispatched
+  /// from here, which makes it a good place to destroy the webview from.
+  WEBVIEW_EVENT_CLOSED
+} webview_event_t;
+
+// Kinds of navigation, see webview_on_navigation
+typedef enum {
+  /// A link was clicked.
+  WEBVIEW_NAVIGATION_LINK,
+  /// A link with target="_blank" or window.open() asked for a new window.
+  WEBVIEW_NAVIGATION_NEW_WINDOW,
+  /// Anything else: scripts, forms, redirects, reloads, frames...
+  WEBVIEW_NAVIGATION_OTHER
+} webview_navigation_t;
+
+// What a file dialog picks, see webview_file_dialog
+typedef enum {
+  /// Existing files.
+  WEBVIEW_FILE_DIALOG_OPEN,
+  /// Where to save a file, confirming before overwriting one.
+  WEBVIEW_FILE_DIALOG_SAVE,
+  /// Existing folders.
+  WEBVIEW_FILE_DIALOG_FOLDER
+} webview_file_dialog_t;
+
+// Kinds of message dialog, see webview_message_dialog
+typedef enum {
+  WEBVIEW_MESSAGE_INFO,
+  WEBVIEW_MESSAGE_WARNING,
+  WEBVIEW_MESSAGE_ERROR,
+  WEBVIEW_MESSAGE_QUESTION
+} webview_message_t;
+
 #include <stdint.h>
 #include <string.h>
 #include <stdlib.h>
@@ -40,7 +95,7 @@ typedef void* webview_t;
 // creation fails.
 WEBVIEW_API webview_t webview_create(int debug, void *window);
 
-// Destroys a webview and closes the native window.
+// Destroys a webview. Its window has to be closed first, see webview_close.
 WEBVIEW_API void webview_destroy(webview_t w);
 
 // Runs the main loop until it's terminated. After this function exits - you
@@ -51,6 +106,10 @@ WEBVIEW_API void webview_run(webview_t w);
 // background thread.
 WEBVIEW_API void webview_terminate(webview_t w);
 
+// Stops the main loop like webview_terminate, without a webview, as every one
+// of them may be destroyed by then.
+WEBVIEW_API void webview_terminate_loop(void);
+
 // Posts a function to be executed on the main thread. You normally do not need
 // to call this function, unless you want to tweak the native window.
 WEBVIEW_API void webview_dispatch(webview_t w, void (*fn)(webview_t w, void *arg), void *arg);
@@ -67,6 +126,122 @@ WEBVIEW_API void webview_set_title(webview_t w, const char *title);
 WEBVIEW_API void webview_set_size(webview_t w, int width, int height,
                                   int hints);
 
+// Sets the smallest size the user can shrink the window to, replacing the one
+// from WEBVIEW_HINT_MIN. A width or height of zero removes the limit.
+WEBVIEW_API void webview_set_min_size(webview_t w, int width, int height);
+
+// Sets the largest size the user can grow the window to, replacing the one
+// from WEBVIEW_HINT_MAX. A width or height of zero removes the limit.
+WEBVIEW_API void webview_set_max_size(webview_t w, int width, int height);
+
+// Keeps the width divided by the height at ratio while the window is resized.
+// A ratio of zero or less unlocks it.
+WEBVIEW_API void webview_set_aspect_ratio(webview_t w, double ratio);
+
+// Moves the window so its top left corner is at x, y on the screen. Not every
+// platform lets windows place themselves (e.g. Wayland), it's a no-op there.
+WEBVIEW_API void webview_set_position(webview_t w, int x, int y);
+
+// Maximizes the window if maximized is non-zero, restores it otherwise.
+WEBVIEW_API void webview_set_maximized(webview_t w, int maximized);
+
+// Minimizes the window if minimized is non-zero, restores it otherwise.
+WEBVIEW_API void webview_set_minimized(webview_t w, int minimized);
+
+// Makes the window fullscreen if fullscreen is non-zero, leaves it otherwise.
+WEBVIEW_API void webview_set_fullscreen(webview_t w, int fullscreen);
+
+// Moves the window to the middle of the screen it's on.
+WEBVIEW_API void webview_center(webview_t w);
+
+// Keeps the window above the others if on_top is non-zero.
+WEBVIEW_API void webview_set_always_on_top(webview_t w, int on_top);
+
+// Shows the window if visible is non-zero, hides it otherwise. Hiding it
+// doesn't stop the main loop.
+WEBVIEW_API void webview_set_visible(webview_t w, int visible);
+
+// Raises the window and gives it the keyboard focus.
+WEBVIEW_API void webview_focus(webview_t w);
+
+// Shows the title bar and borders if decorated is non-zero, hides them
+// otherwise.
+WEBVIEW_API void webview_set_decorated(webview_t w, int decorated);
+
+// Writes the position of the window's top left corner and its size. Platforms
+// that keep the position to themselves (e.g. Wayland) give 0, 0.
+WEBVIEW_API void webview_get_geometry(webview_t w, int *x, int *y, int *width,
+                                      int *height);
+
+// Returns the state of the window, a combination of WEBVIEW_STATE flags.
+WEBVIEW_API int webview_get_state(webview_t w);
+
+// Calls fn with every WEBVIEW_EVENT of the window, replacing the previous
+// function. Its return value only counts for WEBVIEW_EVENT_CLOSE_REQUESTED,
+// where non-zero keeps the window open.
+WEBVIEW_API void webview_on_window_event(webview_t w,
+                                         int (*fn)(int event, int a, int b,
+                                                   void *arg),
+                                         void *arg);
+
+// Whether closing the window stops the main loop, which it does by default.
+// Applications with several windows turn it off and decide for themselves.
+WEBVIEW_API void webview_set_exit_on_close(webview_t w, int exit);
+
+// Asks the window to close, going through WEBVIEW_EVENT_CLOSE_REQUESTED, or
+// closes it right away if force is non-zero.
+WEBVIEW_API void webview_close(webview_t w, int force);
+
+// Asks fn about every navigation the page makes, replacing the previous
+// function. It gets the URL, the one of the current page, the
+// WEBVIEW_NAVIGATION kind and whether the user started it, and returns non-zero
+// to allow it. Allowed new windows open in this one. What's loaded with
+// webview_navigate or webview_set_html is always allowed, redirects included.
+WEBVIEW_API void webview_on_navigation(webview_t w,
+                                       int (*fn)(const char *url,
+                                                 const char *current, int kind,
+                                                 int user_gesture, void *arg),
+                                       void *arg);
+
+// Shows a WEBVIEW_FILE_DIALOG file dialog, modal to the window, and returns
+// right away. Once the user is done, fn is called once with the count picked
+// paths, or with NULL and -1 when the dialog was cancelled. The paths only live
+// as long as the call. title, default_name (only used when saving) and
+// default_folder may be NULL. filter_count filters are made of their
+// filter_names and filter_patterns, the patterns being separated by ';', e.g.
+// "*.png;*.jpg".
+WEBVIEW_API void webview_file_dialog(webview_t w, int action, int multiple,
+                                     const char *title, const char *default_name,
+                                     const char *default_folder,
+                                     const char *const *filter_names,
+                                     const char *const *filter_patterns,
+                                     int filter_count,
+                                     void (*fn)(const char *const *paths,
+                                                int count, void *arg),
+                                     void *arg);
+
+// Shows a WEBVIEW_MESSAGE dialog, modal to the window, and returns right away.
+// Once the user is done, fn is called once with the index of the button
+// clicked, or -1 when the dialog was dismissed, the state of the checkbox and
+// the text typed in. Without buttons, it gets an OK button, along with a Cancel
+// one for questions. title may be NULL. A checkbox labelled checkbox is shown
+// when it's not NULL, checked being its initial state. A text field holding
+// input is shown when that's not NULL, the text fn gets is NULL otherwise or
+// when the dialog was dismissed, and only lives as long as the call.
+WEBVIEW_API void webview_message_dialog(webview_t w, int level,
+                                        const char *title, const char *message,
+                                        const char *const *buttons,
+                                        int button_count, const char *checkbox,
+                                        int checked, const char *input,
+                                        void (*fn)(int button, int checked,
+                                                   const char *input, void *arg),
+                                        void *arg);
+
+// Shows the page's alert(), confirm() and prompt() as native message dialogs
+// titled with the window's title when enabled is non-zero, instead of the
+// engine's own ones.
+WEBVIEW_API void webview_set_native_script_dialogs(webview_t w, int enabled);
+
 // Navigates webview to the given URL. URL may be a properly encoded data URI.
 // Examples:
 // webview_navigate(w, "https://github.com/webview/webview");
@@ -88,6 +263,16 @@ WEBVIEW_API void webview_init(webview_t w, const char *js);
 // receive notifications about the results of the evaluation.
 WEBVIEW_API void webview_eval(webview_t w, const char *js);
 
+// Evaluates arbitrary JavaScript code and calls fn with the result: ok is
+// non-zero and result the value of the last expression as JSON ("null" for
+// undefined), or ok is zero and result the message of the exception thrown.
+// fn is called once, on the main thread, this returns right away. Promises
+// aren't waited for.
+WEBVIEW_API void webview_eval_with_result(webview_t w, const char *js,
+                                          void (*fn)(int ok, const char *result,
+                                                     void *arg),
+                                          void *arg);
+
 // Binds a native C callback so that it will appear under the given name as a
 // global JavaScript function. Internally it uses webview_init(). Callback
 // receives a request string and a user-provided argument pointer. Request
@@ -108,6 +293,22 @@ WEBVIEW_API void webview_unbind(webview_t w, const char *name);
 WEBVIEW_API void webview_return(webview_t w, const char *seq, int status,
                                 const char *result);
 
+// Registers a handler for a custom URI scheme (e.g. "quark"), must be called
+// before navigating to it. The handler receives an opaque request pointer and
+// the path of the requested URI, and answers it with webview_scheme_respond().
+// Requests the handler doesn't respond to fail with a "not found" error.
+WEBVIEW_API void webview_register_scheme(webview_t w, const char *scheme,
+                                         void (*fn)(void *request,
+                                                    const char *path,
+                                                    void *arg),
+                                         void *arg);
+
+// Answers a custom URI scheme request with the given body and MIME type. The
+// data is copied, so it only has to be valid for the duration of the call.
+// Must be called from within the scheme handler, at most once per request.
+WEBVIEW_API void webview_scheme_respond(void *request, const void *data,
+                                        size_t length, const char *mime_type);
+
 #ifdef __cplusplus
 }
 
@@ -35,6 +35,10 @@ extern "C" {
       static_cast<webview::webview *>(w)->terminate();
     }
 
+    WEBVIEW_API void webview_terminate_loop(void) {
+      webview::webview::terminate_loop();
+    }
+
     WEBVIEW_API void webview_dispatch(webview_t w, void (*fn)(webview_t, void *),
                                       void *arg) {
       static_cast<webview::webview *>(w)->dispatch([=]() { fn(w, arg); });
@@ -53,6 +57,131 @@ extern "C" {
       static_cast<webview::webview *>(w)->set_size(width, height, hints);
     }
 
+    WEBVIEW_API void webview_set_min_size(webview_t w, int width, int height) {
+      static_cast<webview::webview *>(w)->set_min_size(width, height);
+    }
+
+    WEBVIEW_API void webview_set_max_size(webview_t w, int width, int height) {
+      static_cast<webview::webview *>(w)->set_max_size(width, height);
+    }
+
+    WEBVIEW_API void webview_set_aspect_ratio(webview_t w, double ratio) {
+      static_cast<webview::webview *>(w)->set_aspect_ratio(ratio);
+    }
+
+    WEBVIEW_API void webview_set_position(webview_t w, int x, int y) {
+      static_cast<webview::webview *>(w)->set_position(x, y);
+    }
+
+    WEBVIEW_API void webview_set_maximized(webview_t w, int maximized) {
+      static_cast<webview::webview *>(w)->set_maximized(maximized != 0);
+    }
+
+    WEBVIEW_API void webview_set_minimized(webview_t w, int minimized) {
+      static_cast<webview::webview *>(w)->set_minimized(minimized != 0);
+    }
+
+    WEBVIEW_API void webview_set_fullscreen(webview_t w, int fullscreen) {
+      static_cast<webview::webview *>(w)->set_fullscreen(fullscreen != 0);
+    }
+
+    WEBVIEW_API void webview_center(webview_t w) {
+      static_cast<webview::webview *>(w)->center();
+    }
+
+    WEBVIEW_API void webview_set_always_on_top(webview_t w, int on_top) {
+      static_cast<webview::webview *>(w)->set_always_on_top(on_top != 0);
+    }
+
+    WEBVIEW_API void webview_set_visible(webview_t w, int visible) {
+      static_cast<webview::webview *>(w)->set_visible(visible != 0);
+    }
+
+    WEBVIEW_API void webview_focus(webview_t w) {
+      static_cast<webview::webview *>(w)->focus();
+    }
+
+    WEBVIEW_API void webview_set_decorated(webview_t w, int decorated) {
+      static_cast<webview::webview *>(w)->set_decorated(decorated != 0);
+    }
+
+    WEBVIEW_API void webview_get_geometry(webview_t w, int *x, int *y,
+                                          int *width, int *height) {
+      static_cast<webview::webview *>(w)->get_geometry(x, y, width, height);
+    }
+
+    WEBVIEW_API int webview_get_state(webview_t w) {
+      return static_cast<webview::webview *>(w)->get_state();
+    }
+
+    WEBVIEW_API void webview_on_window_event(webview_t w,
+                                             int (*fn)(int event, int a, int b,
+                                                       void *arg),
+                                             void *arg) {
+      static_cast<webview::webview *>(w)->on_window_event(
+          [=](int event, int a, int b) { return fn(event, a, b, arg); });
+    }
+
+    WEBVIEW_API void webview_set_exit_on_close(webview_t w, int exit) {
+      static_cast<webview::webview *>(w)->set_exit_on_close(exit != 0);
+    }
+
+    WEBVIEW_API void webview_close(webview_t w, int force) {
+      static_cast<webview::webview *>(w)->close(force != 0);
+    }
+
+    WEBVIEW_API void webview_on_navigation(webview_t w,
+                                           int (*fn)(const char *url,
+                                                     const char *current,
+                                                     int kind, int user_gesture,
+                                                     void *arg),
+                                           void *arg) {
+      static_cast<webview::webview *>(w)->on_navigation(
+          [=](const char *url, const char *current, int kind, bool user_gesture) {
+            return fn(url, current, kind, user_gesture, arg) != 0;
+          });
+    }
+
+    WEBVIEW_API void webview_file_dialog(webview_t w, int action, int multiple,
+                                         const char *title,
+                                         const char *default_name,
+                                         const char *default_folder,
+                                         const char *const *filter_names,
+                                         const char *const *filter_patterns,
+                                         int filter_count,
+                                         void (*fn)(const char *const *paths,
+                                                    int count, void *arg),
+                                         void *arg) {
+      static_cast<webview::webview *>(w)->file_dialog(
+          action, multiple != 0, title, default_name, default_folder,
+          filter_names, filter_patterns, filter_count,
+          [=](const char *const *paths, int count) { fn(paths, count, arg); });
+    }
+
+    WEBVIEW_API void webview_message_dialog(webview_t w, int level,
+                                            const char *title,
+                                            const char *message,
+                                            const char *const *buttons,
+                                            int button_count,
+                                            const char *checkbox, int checked,
+                                            const char *input,
+                                            void (*fn)(int button, int checked,
+                                                       const char *input,
+                                                       void *arg),
+                                            void *arg) {
+      static_cast<webview::webview *>(w)->message_dialog(
+          level, title, message, buttons, button_count, checkbox, checked != 0,
+          input, [=](int button, bool checked, const char *text) {
+            fn(button, checked, text, arg);
+          });
+    }
+
+    WEBVIEW_API void webview_set_native_script_dialogs(webview_t w,
+                                                       int enabled) {
+      static_cast<webview::webview *>(w)->set_native_script_dialogs(enabled !=
+                                                                    0);
+    }
+
     WEBVIEW_API void webview_navigate(webview_t w, const char *url) {
       static_cast<webview::webview *>(w)->navigate(url);
     }
@@ -69,6 +198,15 @@ extern "C" {
       static_cast<webview::webview *>(w)->eval(js);
     }
 
+    WEBVIEW_API void webview_eval_with_result(webview_t w, const char *js,
+                                              void (*fn)(int ok,
+                                                         const char *result,
+                                                         void *arg),
+                                              void *arg) {
+      static_cast<webview::webview *>(w)->eval_with_result(
+          js, [=](bool ok, const char *result) { fn(ok, result, arg); });
+    }
+
     WEBVIEW_API void webview_bind(webview_t w, const char *name,
                                   void (*fn)(const char *seq, const char *req,
                                              void *arg),
@@ -90,4 +228,21 @@ extern "C" {
       static_cast<webview::webview *>(w)->resolve(seq, status, result);
     }
 
+    WEBVIEW_API void webview_register_scheme(webview_t w, const char *scheme,
+                                             void (*fn)(void *request,
+                                                        const char *path,
+                                                        void *arg),
+                                             void *arg) {
+      static_cast<webview::webview *>(w)->register_scheme(
+          scheme, [=](void *request, const std::string &path) {
+            fn(request, path.c_str(), arg);
+          });
+    }
+
+    WEBVIEW_API void webview_scheme_respond(void *request, const void *data,
+                                            size_t length,
+                                            const char *mime_type) {
+      webview::webview::scheme_respond(request, data, length, mime_type);
+    }
+
 }
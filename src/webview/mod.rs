@@ -1,8 +1,25 @@
-pub use binding::{SizeHint, Webview, Window};
+pub(crate) use binding::supported;
+pub use binding::{
+    Dispatcher, FileDialogAction, MessageLevel, MessageResponse, NavigationKind, SizeHint, Webview,
+    Window, WindowEvent, WindowGeometry, WindowState,
+};
 pub use builder::WebviewBuilder;
-use std::os::raw::{c_char, c_int, c_ushort, c_void};
+use std::os::raw::{c_char, c_double, c_int, c_ushort, c_void};
 pub type DispatchFn = extern "C" fn(webview: webview_t, arg: *mut c_void);
 pub type BindFn = extern "C" fn(seq: *const c_char, req: *const c_char, arg: *mut c_void);
+pub type WindowEventFn = extern "C" fn(event: c_int, a: c_int, b: c_int, arg: *mut c_void) -> c_int;
+pub type NavigationFn = extern "C" fn(
+    url: *const c_char,
+    current: *const c_char,
+    kind: c_int,
+    user_gesture: c_int,
+    arg: *mut c_void,
+) -> c_int;
+pub type FileDialogFn = extern "C" fn(paths: *const *const c_char, count: c_int, arg: *mut c_void);
+pub type MessageDialogFn =
+    extern "C" fn(button: c_int, checked: c_int, input: *const c_char, arg: *mut c_void);
+pub type EvalFn = extern "C" fn(ok: c_int, result: *const c_char, arg: *mut c_void);
+pub type SchemeFn = extern "C" fn(request: *mut c_void, path: *const c_char, arg: *mut c_void);
 
 mod binding;
 mod builder;
@@ -19,6 +36,8 @@ extern "C" {
 
     pub fn webview_terminate(w: webview_t);
 
+    pub fn webview_terminate_loop();
+
     pub fn webview_dispatch(w: webview_t, fn_: Option<DispatchFn>, arg: *mut c_void);
 
     pub fn webview_get_window(w: webview_t) -> *mut c_void;
@@ -29,13 +48,102 @@ extern "C" {
 
     pub fn webview_set_size(w: webview_t, width: c_ushort, height: c_ushort, hints: c_int);
 
+    pub fn webview_set_min_size(w: webview_t, width: c_int, height: c_int);
+
+    pub fn webview_set_max_size(w: webview_t, width: c_int, height: c_int);
+
+    pub fn webview_set_aspect_ratio(w: webview_t, ratio: c_double);
+
+    pub fn webview_set_position(w: webview_t, x: c_int, y: c_int);
+
+    pub fn webview_set_maximized(w: webview_t, maximized: c_int);
+
+    pub fn webview_set_minimized(w: webview_t, minimized: c_int);
+
+    pub fn webview_set_fullscreen(w: webview_t, fullscreen: c_int);
+
+    pub fn webview_center(w: webview_t);
+
+    pub fn webview_set_always_on_top(w: webview_t, on_top: c_int);
+
+    pub fn webview_set_visible(w: webview_t, visible: c_int);
+
+    pub fn webview_focus(w: webview_t);
+
+    pub fn webview_set_decorated(w: webview_t, decorated: c_int);
+
+    pub fn webview_get_geometry(
+        w: webview_t,
+        x: *mut c_int,
+        y: *mut c_int,
+        width: *mut c_int,
+        height: *mut c_int,
+    );
+
+    pub fn webview_get_state(w: webview_t) -> c_int;
+
+    pub fn webview_on_window_event(w: webview_t, fn_: Option<WindowEventFn>, arg: *mut c_void);
+
+    pub fn webview_set_exit_on_close(w: webview_t, exit: c_int);
+
+    pub fn webview_close(w: webview_t, force: c_int);
+
+    pub fn webview_on_navigation(w: webview_t, fn_: Option<NavigationFn>, arg: *mut c_void);
+
+    pub fn webview_file_dialog(
+        w: webview_t,
+        action: c_int,
+        multiple: c_int,
+        title: *const c_char,
+        default_name: *const c_char,
+        default_folder: *const c_char,
+        filter_names: *const *const c_char,
+        filter_patterns: *const *const c_char,
+        filter_count: c_int,
+        fn_: Option<FileDialogFn>,
+        arg: *mut c_void,
+    );
+    pub fn webview_message_dialog(
+        w: webview_t,
+        level: c_int,
+        title: *const c_char,
+        message: *const c_char,
+        buttons: *const *const c_char,
+        button_count: c_int,
+        checkbox: *const c_char,
+        checked: c_int,
+        input: *const c_char,
+        fn_: Option<MessageDialogFn>,
+        arg: *mut c_void,
+    );
+    pub fn webview_set_native_script_dialogs(w: webview_t, enabled: c_int);
     pub fn webview_navigate(w: webview_t, url: *const c_char);
 
     pub fn webview_init(w: webview_t, js: *const c_char);
 
     pub fn webview_eval(w: webview_t, js: *const c_char);
+    pub fn webview_eval_with_result(
+        w: webview_t,
+        js: *const c_char,
+        fn_: Option<EvalFn>,
+        arg: *mut c_void,
+    );
 
     pub fn webview_bind(w: webview_t, name: *const c_char, fn_: Option<BindFn>, arg: *mut c_void);
 
     pub fn webview_return(w: webview_t, seq: *const c_char, status: c_int, result: *const c_char);
+
+    pub fn webview_register_scheme(
+        w: webview_t,
+        scheme: *const c_char,
+        fn_: Option<SchemeFn>,
+        arg: *mut c_void,
+    );
+
+    pub fn webview_scheme_respond(
+        request: *mut c_void,
+        data: *const c_void,
+        length: usize,
+        mime_type: *const c_char,
+    );
 }
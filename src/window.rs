@@ -0,0 +1,258 @@
+//! Managing the window from JavaScript, Rust has the same methods on [`Quark`] and
+//! [`QuarkHandle`].
+//!
+//! Every page gets a `quark.window` object, whose methods return promises:
+//!
+//! ```js
+//! await quark.window.toggleFullscreen();
+//! const { width, height } = await quark.window.geometry();
+//! if (!(await quark.window.state()).maximized) { await quark.window.maximize(); }
+//! ```
+//!
+//! The window's events reach the page through `quark.on`: `window:resized` (with `width` and
+//! `height`), `window:moved` (with `x` and `y`), `window:focused`, `window:blurred`, and
+//! `window:maximized`, `window:minimized` and `window:fullscreen` with whether it now is. Closing
+//! can be stopped with `quark.window.onCloseRequested`, its handlers may be async:
+//!
+//! ```js
+//! quark.window.onCloseRequested(async (event) => {
+//!   if (unsaved && !(await askToDiscard())) { event.preventDefault(); }
+//! });
+//! ```
+//!
+//! macOS doesn't send window events yet: the page gets none of them there, and
+//! `onCloseRequested` handlers never run, an unhandled rejection in the console says so.
+//!
+//! [`Quark`]: crate::Quark
+//! [`QuarkHandle`]: crate::handle::QuarkHandle
+
+use crate::command;
+use crate::event;
+use crate::webview::{Webview, WindowEvent};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The binding `quark.window` calls through.
+const WINDOW_BINDING: &str = "__quark_window";
+
+/// How `QuarkError::Unsupported` calls them.
+pub(crate) const WINDOW_EVENTS: &str = "window events";
+
+/// Injected after the binding, so every new page starts without close handlers in Rust's eyes.
+const RESET_SCRIPT: &str = r#"window.__quark_window({ op: "interceptClose", arg: false });"#;
+
+/// Injected into every page, before any of its own scripts run.
+const SCRIPT: &str = r#"(function() {
+  var quark = window.quark = window.quark || {};
+  var call = function(op, arg) {
+    return window.__quark_window({ op: op, arg: arg });
+  };
+  quark.window = {
+    maximize: function() { return call("maximize"); },
+    unmaximize: function() { return call("unmaximize"); },
+    minimize: function() { return call("minimize"); },
+    unminimize: function() { return call("unminimize"); },
+    setFullscreen: function(fullscreen) { return call("setFullscreen", !!fullscreen); },
+    toggleFullscreen: function() { return call("toggleFullscreen"); },
+    setPosition: function(x, y) { return call("setPosition", [x, y]); },
+    center: function() { return call("center"); },
+    setAlwaysOnTop: function(onTop) { return call("setAlwaysOnTop", !!onTop); },
+    show: function() { return call("show"); },
+    hide: function() { return call("hide"); },
+    focus: function() { return call("focus"); },
+    setDecorations: function(decorations) { return call("setDecorations", !!decorations); },
+    geometry: function() { return call("geometry"); },
+    state: function() { return call("state"); },
+    close: function() { return call("close"); },
+    destroy: function() { return call("destroy"); },
+    onCloseRequested: function(handler) {
+      closeHandlers.push(handler);
+      if (closeHandlers.length === 1) { call("interceptClose", true); }
+      return function() {
+        var i = closeHandlers.indexOf(handler);
+        if (i === -1) { return; }
+        closeHandlers.splice(i, 1);
+        if (closeHandlers.length === 0) { call("interceptClose", false); }
+      };
+    }
+  };
+  var closeHandlers = [];
+  quark.on("window:close-requested", function() {
+    var prevented = false;
+    var event = { preventDefault: function() { prevented = true; } };
+    Promise.all(closeHandlers.slice().map(function(handler) {
+      try { return handler(event); } catch (e) { console.error(e); }
+    })).then(done, done);
+    // A handler that rejects doesn't keep the window open, unless it prevented it already
+    function done() {
+      if (!prevented) { call("destroy"); }
+    }
+  });
+})();"#;
+
+#[derive(Deserialize)]
+#[serde(tag = "op", content = "arg", rename_all = "camelCase")]
+enum Op {
+    Maximize,
+    Unmaximize,
+    Minimize,
+    Unminimize,
+    SetFullscreen(bool),
+    ToggleFullscreen,
+    SetPosition(i32, i32),
+    Center,
+    SetAlwaysOnTop(bool),
+    Show,
+    Hide,
+    Focus,
+    SetDecorations(bool),
+    Geometry,
+    State,
+    Close,
+    Destroy,
+    InterceptClose(bool),
+}
+
+type CloseHandler = Box<dyn FnMut() -> bool>;
+type EventHandler = Box<dyn FnMut(&WindowEvent)>;
+
+/// The Rust handlers of a window's events.
+#[derive(Clone, Default)]
+pub(crate) struct WindowListeners(Rc<RefCell<Handlers>>);
+
+#[derive(Default)]
+struct Handlers {
+    close: Vec<CloseHandler>,
+    events: Vec<EventHandler>,
+    /// Whether the page has `quark.window.onCloseRequested` handlers
+    page_handles_close: bool,
+}
+
+impl WindowListeners {
+    pub(crate) fn on_close_requested(&self, handler: CloseHandler) {
+        self.0.borrow_mut().close.push(handler);
+    }
+
+    pub(crate) fn on_window_event(&self, handler: EventHandler) {
+        self.0.borrow_mut().events.push(handler);
+    }
+
+    /// Runs the handlers of `event`, returning whether the window may close.
+    fn call(&self, event: WindowEvent) -> bool {
+        // The handlers are taken out while they run, as they may add handlers themselves or
+        // force the window closed, which sends `WindowEvent::Closed` right away
+        let mut events = std::mem::take(&mut self.0.borrow_mut().events);
+        for handler in events.iter_mut() {
+            handler(&event);
+        }
+        let mut handlers = self.0.borrow_mut();
+        events.append(&mut handlers.events);
+        handlers.events = events;
+        drop(handlers);
+
+        if event != WindowEvent::CloseRequested {
+            return true;
+        }
+        let mut close = std::mem::take(&mut self.0.borrow_mut().close);
+        let allowed = close.iter_mut().all(|handler| handler());
+        let mut handlers = self.0.borrow_mut();
+        close.append(&mut handlers.close);
+        handlers.close = close;
+        allowed
+    }
+
+    fn set_page_handles_close(&self, handles: bool) {
+        self.0.borrow_mut().page_handles_close = handles;
+    }
+
+    fn page_handles_close(&self) -> bool {
+        self.0.borrow().page_handles_close
+    }
+}
+
+/// Injects `quark.window` into `webview` and sends the window's events to `listeners` and to
+/// the page.
+pub(crate) fn install(webview: &mut Webview, listeners: WindowListeners) {
+    webview.init(SCRIPT);
+
+    let mut window = webview.clone();
+    let page = listeners.clone();
+    webview.bind(WINDOW_BINDING, move |seq, req| {
+        let (status, result) = match command::decode_args::<(Op,)>(req) {
+            Ok((Op::InterceptClose(intercept),)) => {
+                match crate::webview::supported(WINDOW_EVENTS) {
+                    // The page's handlers would never run, the unhandled rejection tells it why
+                    Err(e) if intercept => command::settle(Err::<(), _>(e.to_string())),
+                    _ => {
+                        page.set_page_handles_close(intercept);
+                        (command::RESOLVE, "null".to_owned())
+                    }
+                }
+            }
+            Ok((op,)) => command::settle(Ok::<_, ()>(apply(&mut window, op))),
+            Err(e) => command::invalid_args("quark.window", &e),
+        };
+        window.r#return(seq, status, &result);
+    });
+    // Reloading or navigating drops the page's handlers along with the page
+    webview.init(RESET_SCRIPT);
+
+    let mut window = webview.clone();
+    webview.on_window_event(move |event| {
+        if !listeners.call(event) {
+            return false;
+        }
+        let (name, payload) = match event {
+            // The page gets to decide, it closes the window itself if it agrees
+            WindowEvent::CloseRequested if listeners.page_handles_close() => {
+                ("window:close-requested", Value::Null)
+            }
+            WindowEvent::CloseRequested | WindowEvent::Closed => return true,
+            WindowEvent::Resized { width, height } => (
+                "window:resized",
+                json!({ "width": width, "height": height }),
+            ),
+            WindowEvent::Moved { x, y } => ("window:moved", json!({ "x": x, "y": y })),
+            WindowEvent::Focused(true) => ("window:focused", Value::Null),
+            WindowEvent::Focused(false) => ("window:blurred", Value::Null),
+            WindowEvent::Maximized(maximized) => ("window:maximized", json!(maximized)),
+            WindowEvent::Minimized(minimized) => ("window:minimized", json!(minimized)),
+            WindowEvent::Fullscreen(fullscreen) => ("window:fullscreen", json!(fullscreen)),
+        };
+        if let Ok(js) = event::emit_js(name, &payload) {
+            window.eval(&js);
+        }
+        event != WindowEvent::CloseRequested
+    });
+}
+
+/// Runs `op` on the window, returning what the promise resolves with.
+fn apply(window: &mut Webview, op: Op) -> Value {
+    match op {
+        Op::Maximize => window.set_maximized(true),
+        Op::Unmaximize => window.set_maximized(false),
+        Op::Minimize => window.set_minimized(true),
+        Op::Unminimize => window.set_minimized(false),
+        Op::SetFullscreen(fullscreen) => window.set_fullscreen(fullscreen),
+        Op::ToggleFullscreen => {
+            let fullscreen = window.state().fullscreen;
+            window.set_fullscreen(!fullscreen);
+        }
+        Op::SetPosition(x, y) => window.set_position(x, y),
+        Op::Center => window.center(),
+        Op::SetAlwaysOnTop(on_top) => window.set_always_on_top(on_top),
+        Op::Show => window.set_visible(true),
+        Op::Hide => window.set_visible(false),
+        Op::Focus => window.focus(),
+        Op::SetDecorations(decorations) => window.set_decorations(decorations),
+        // Plain structs of numbers and booleans always serialize
+        Op::Geometry => return serde_json::to_value(window.geometry()).unwrap_or_default(),
+        Op::State => return serde_json::to_value(window.state()).unwrap_or_default(),
+        Op::Close => window.close(false),
+        Op::Destroy => window.close(true),
+        Op::InterceptClose(_) => {} // handled by the binding
+    }
+    Value::Null
+}
@@ -0,0 +1,36 @@
+use libquark::prelude::*;
+use std::time::Duration;
+
+fn main() -> Result<(), QuarkError> {
+    // The window settings are in Quark.toml
+    let mut quark = Quark::new(manifest!()?)?;
+
+    // `await greet("you")` in JavaScript
+    quark.command("greet", |(name,): (String,)| -> Result<String, String> {
+        if name.trim().is_empty() {
+            return Err("Who are you?".to_owned());
+        }
+        Ok(format!("Hello, {name}! Greetings from Rust."))
+    });
+
+    // Runs on a worker thread so the window doesn't freeze
+    quark.command_async("countdown", |(from,): (u32,)| async move {
+        std::thread::sleep(Duration::from_millis(100 * u64::from(from)));
+        Ok::<_, String>(format!("Counted down from {from}"))
+    });
+
+    // Rust -> JavaScript events, from any thread
+    let handle = quark.handle();
+    quark.listen("start-clock", move |_: ()| {
+        let handle = handle.clone();
+        std::thread::spawn(move || {
+            for tick in 1..=5 {
+                std::thread::sleep(Duration::from_secs(1));
+                handle.emit("tick", tick);
+            }
+        });
+    });
+
+    quark.run();
+    Ok(())
+}
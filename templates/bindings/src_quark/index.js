@@ -0,0 +1,21 @@
+const output = document.getElementById("output");
+quark.on("tick", (tick) => (output.textContent = `Tick ${tick}`));
+
+document.getElementById("greet").addEventListener("submit", async (event) => {
+    event.preventDefault();
+    const greeting = document.getElementById("greeting");
+    try {
+        greeting.textContent = await greet(document.getElementById("name").value);
+    } catch (error) {
+        greeting.textContent = `Rust said no: ${error}`;
+    }
+});
+
+document.getElementById("countdown").addEventListener("click", async () => {
+    output.textContent = "Counting...";
+    output.textContent = await countdown(10);
+});
+
+document.getElementById("clock").addEventListener("click", () => {
+    quark.emit("start-clock");
+});
@@ -0,0 +1,9 @@
+use libquark::prelude::*;
+
+fn main() -> Result<(), QuarkError> {
+    // The window settings are in Quark.toml
+    let mut quark = Quark::new(manifest!()?)?;
+    quark.listen("ready", |message: String| println!("{message}"));
+    quark.run();
+    Ok(())
+}
@@ -0,0 +1,6 @@
+const title = document.querySelector("h1");
+if (title) {
+    title.textContent += ` (${quark.args.length} argument(s))`;
+}
+
+quark.emit("ready", "{{name}} is up and running!");
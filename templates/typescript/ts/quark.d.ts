@@ -0,0 +1,14 @@
+// What Quark injects into every page
+interface Quark {
+    /** The command line arguments meant for the application. */
+    readonly args: readonly string[];
+    /** Calls `handler` every time Rust emits `event`, returns a function that stops listening. */
+    on<T = unknown>(event: string, handler: (payload: T) => void): () => void;
+    /** Like `on`, but only for the next time. */
+    once<T = unknown>(event: string, handler: (payload: T) => void): () => void;
+    off(event: string, handler: (payload: any) => void): void;
+    /** Sends `event` to the `Quark::listen` handlers. */
+    emit(event: string, payload?: unknown): Promise<void>;
+}
+
+declare const quark: Quark;
@@ -0,0 +1,8 @@
+use libquark::prelude::*;
+
+fn main() -> Result<(), QuarkError> {
+    // The window settings are in Quark.toml
+    let quark = Quark::new(manifest!()?)?;
+    quark.run();
+    Ok(())
+}
@@ -1,9 +1,5 @@
-use libquark::prelude::*;
-
 #[cfg(test)]
 mod quark_lib {
-    use super::*;
-
     #[test]
     fn println() {
         // wait I'll make test for cli/bundle later... soon... i think..
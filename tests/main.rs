@@ -46,6 +46,7 @@ mod quark_lib {
     fn custom_config() {
         let config = QuarkConfig::new()
             .title("QuarkTestWindowConfig")
+            .frontend(frontend!())
             .width(1024)
             .height(768)
             .resizable(SizeHint::FIXED);
@@ -54,6 +55,50 @@ mod quark_lib {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn app() {
+        let app = QuarkApp::new(QuarkConfig::new().frontend(frontend!()));
+        app.on_window_created(|quark| quark.bind("label", |_, _| {}));
+        assert!(app.open("main", app.config()).is_ok());
+        assert!(app
+            .open("preferences", app.config().route("/preferences.html"))
+            .is_ok());
+        assert!(matches!(
+            app.open("main", app.config()),
+            Err(QuarkError::WindowAlreadyOpen(_))
+        ));
+        assert_eq!(app.labels(), ["main", "preferences"]);
+        assert!(app.window("preferences").is_some());
+    }
+
+    #[test]
+    fn size_constraints() {
+        let config = QuarkConfig::new()
+            .frontend(frontend!())
+            .resizable(SizeHint::NONE)
+            .min_size(400, 300)
+            .max_size(1600, 1200)
+            .lock_aspect_ratio(true)
+            .position(100, 100);
+        assert!(Quark::new(config).is_ok());
+
+        let config = QuarkConfig::new()
+            .frontend(frontend!())
+            .min_size(800, 600)
+            .max_size(400, 300);
+        assert!(matches!(
+            Quark::new(config),
+            Err(QuarkError::InvalidWindowSize(_))
+        ));
+
+        // The default `SizeHint::MAX` caps the window at its 800x600 size
+        let config = QuarkConfig::new().frontend(frontend!()).min_size(1024, 768);
+        assert!(matches!(
+            Quark::new(config),
+            Err(QuarkError::InvalidWindowSize(_))
+        ));
+    }
+
     // #[test]
     // fn path() {
     //     assert_html_exists();
@@ -63,11 +108,193 @@ mod quark_lib {
     //     assert!(full_path.exists());
     // }
 
+    #[test]
+    fn assets() {
+        use libquark::cli::build_static::asset_path;
+        use libquark::mime;
+
+        assert_eq!(asset_path("/"), "index.html");
+        assert_eq!(asset_path("/index.js"), "index.js");
+        assert_eq!(asset_path("/docs/"), "docs/index.html");
+        assert_eq!(asset_path("/img/a%20b.png"), "img/a b.png");
+
+        assert_eq!(
+            mime::from_path("index.js"),
+            "text/javascript; charset=utf-8"
+        );
+        assert_eq!(mime::from_path("font.WOFF2"), "font/woff2");
+        assert_eq!(mime::from_path("LICENSE"), mime::DEFAULT);
+    }
+
+    #[test]
+    fn command_args() {
+        use libquark::command::decode_args;
+
+        let (name, count): (String, u32) = decode_args(r#"["quark", 3]"#).unwrap();
+        assert_eq!((name.as_str(), count), ("quark", 3));
+        assert!(decode_args::<()>("[]").is_ok());
+        assert!(decode_args::<(u32,)>(r#"["not a number"]"#).is_err());
+    }
+
+    #[test]
+    fn handle_is_thread_safe() {
+        fn assert_send_sync<T: Send + Sync + Clone>() {}
+        assert_send_sync::<QuarkHandle>();
+        // handed to `QuarkHandle::file_dialog` and `QuarkHandle::message_dialog`
+        assert_send_sync::<FileDialog>();
+        assert_send_sync::<MessageDialog>();
+    }
+
+    #[test]
+    fn ranges() {
+        use libquark::cli::build_http::parse_range;
+
+        assert_eq!(parse_range("bytes=0-99", 1000), Some(Some((0, 99))));
+        assert_eq!(parse_range("bytes=500-", 1000), Some(Some((500, 999))));
+        assert_eq!(parse_range("bytes=-100", 1000), Some(Some((900, 999))));
+        assert_eq!(parse_range("bytes=900-5000", 1000), Some(Some((900, 999))));
+        assert_eq!(parse_range("bytes=1000-", 1000), Some(None));
+        assert_eq!(parse_range("bytes=-0", 1000), Some(None));
+        assert_eq!(parse_range("bytes=50-10", 1000), None);
+        assert_eq!(parse_range("bytes=0-1,5-9", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+    }
+
+    #[test]
+    fn urls() {
+        use libquark::url::validate;
+
+        for url in [
+            "https://example.com",
+            "http://127.0.0.1:24114/index.html",
+            "http://[::1]:8080/",
+            "quark://app/index.html",
+            "file:///home/quark/index.html",
+            "about:blank",
+            "data:text/html,%3Ch1%3EHello%3C%2Fh1%3E",
+        ] {
+            assert!(validate(url).is_ok(), "{url} should be valid");
+        }
+
+        for url in [
+            "",
+            "example.com",
+            "https://",
+            "https:example.com",
+            "http://localhost:99999/",
+            "https://exa mple.com",
+            "1http://example.com",
+            "javascript:alert(1)",
+            "JavaScript:void(0)",
+        ] {
+            assert!(
+                matches!(validate(url), Err(QuarkError::InvalidUrl(_))),
+                "{url} should be invalid"
+            );
+        }
+
+        use libquark::url::origin;
+        assert_eq!(
+            origin("HTTPS://Example.com:443/a?b#c").as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(
+            origin("http://user@localhost:5173/").as_deref(),
+            Some("http://localhost:5173")
+        );
+        assert_eq!(
+            origin("quark://app/index.html").as_deref(),
+            Some("quark://app")
+        );
+        assert_eq!(
+            origin("blob:https://example.com/1234").as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(origin("about:blank"), None);
+        assert_eq!(origin("mailto:quark@example.com"), None);
+    }
+
+    #[test]
+    fn console_messages() {
+        let mut message = ConsoleMessage {
+            level: log::Level::Error,
+            kind: ConsoleKind::UncaughtError,
+            message: String::from("TypeError: undefined is not an object"),
+            source: Some(String::from("quark://app/main.js")),
+            line: Some(12),
+            column: Some(8),
+        };
+        assert_eq!(
+            message.to_string(),
+            "Uncaught TypeError: undefined is not an object (quark://app/main.js:12:8)"
+        );
+        message.kind = ConsoleKind::Console;
+        message.source = None;
+        assert_eq!(message.to_string(), "TypeError: undefined is not an object");
+    }
+
+    #[test]
+    fn manifest() {
+        use libquark::manifest::Manifest;
+
+        let manifest = Manifest::parse(
+            r#"
+            [window]
+            title = "Manifest"
+            width = 1024
+            resizable = "none"
+            min_size = [400, 300]
+            max_size = [1600, 1200]
+            lock_aspect_ratio = true
+            native_script_dialogs = true
+
+            [dev]
+            port = 3000
+
+            [bundle]
+            identifier = "com.example.manifest"
+            icon = ["icons/icon*.png"]
+            "#,
+        )
+        .expect("Failed to parse the manifest");
+        assert_eq!(manifest.window.resizable, Some(SizeHint::NONE));
+        assert_eq!(manifest.window.max_size, Some((1600, 1200)));
+        assert_eq!(manifest.window.native_script_dialogs, Some(true));
+        assert_eq!(manifest.dev.port, Some(3000));
+        assert_eq!(manifest.frontend_dir(), "src_quark");
+        assert!(Manifest::parse("").is_ok());
+
+        for source in [
+            "[window]\ntitel = \"typo\"",
+            "[window]\nwidth = \"wide\"",
+            "[window]\nwidth = 0",
+            "[window]\nresizable = \"sometimes\"",
+            "[window]\nmin_size = [800, 600]\nmax_size = [400, 300]",
+            "[window]\nmax_size = [0, 300]",
+            "[window]\nposition = [100]",
+            "[frontend]\ndir = \"../elsewhere\"",
+            "[dev]\nurl = \"localhost:5173\"",
+            "[bundle]\nidentifier = \"not an identifier\"",
+            "[windows]",
+        ] {
+            assert!(
+                matches!(Manifest::parse(source), Err(QuarkError::InvalidManifest(_))),
+                "{source:?} should be invalid"
+            );
+        }
+    }
+
     #[test]
     fn errors() {
         let errors = [
             QuarkError::FrontendPathMissing,
+            QuarkError::FileWatcherError,
             QuarkError::IncludeDirCouldntConvertToUTF8,
+            QuarkError::InvalidUrl(String::from("not a url")),
+            QuarkError::InvalidManifest(String::from("Quark.toml: unknown field")),
+            QuarkError::InvalidWindowSize(String::from("min_size is 0x0")),
+            QuarkError::WindowAlreadyOpen(String::from("main")),
+            QuarkError::Unsupported(String::from("window events")),
             QuarkError::ServerPortIsntAvailable,
             QuarkError::ServerError,
         ];